@@ -0,0 +1,112 @@
+use std::{env, fs, path::Path};
+
+fn main() {
+    examples();
+    if env::var_os("CARGO_FEATURE_EMBED").is_some() {
+        embedded();
+    }
+}
+
+/// writes one test per example case and part with an expected answer, so `cargo test` lists
+/// every case separately. The tests are included by `tests/examples.rs`.
+fn examples() {
+    let examples = Path::new("data/examples");
+    println!("cargo:rerun-if-changed={}", examples.display());
+
+    let mut cases = vec![];
+    if let Ok(days) = fs::read_dir(examples) {
+        for day in days.flatten().filter(|d| d.path().is_dir()) {
+            println!("cargo:rerun-if-changed={}", day.path().display());
+            for case in fs::read_dir(day.path()).unwrap().flatten() {
+                let path = case.path();
+                if path.extension().is_some_and(|ext| ext == "txt") {
+                    cases.push(path);
+                }
+            }
+        }
+    }
+    cases.sort();
+
+    let mut tests = String::new();
+    for path in cases {
+        let text = fs::read_to_string(&path).unwrap();
+        let day = path
+            .parent()
+            .unwrap()
+            .file_name()
+            .unwrap()
+            .to_string_lossy();
+        let case = path.file_stem().unwrap().to_string_lossy();
+        let ident: String = format!("{}_{}", day, case)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        // only the header is of interest here, the examples module validates the rest
+        let header = text.lines().take_while(|line| line.trim_end() != "---");
+        for line in header {
+            let part = match line.split(':').next().map(str::trim) {
+                Some("part1") => "one",
+                Some("part2") => "two",
+                _ => continue,
+            };
+            tests += &format!(
+                "#[test]\nfn {}_part_{}() {{\n    check({:?}, {});\n}}\n\n",
+                ident,
+                part,
+                path.display().to_string(),
+                if part == "one" { 1 } else { 2 },
+            );
+        }
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("examples.rs");
+    fs::write(out, tests).unwrap();
+}
+
+/// writes the `embedded` module, with every `data/dayN.txt` input and the expected answers
+/// compiled in through `include_str!`.
+fn embedded() {
+    let data = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("data");
+    println!("cargo:rerun-if-changed={}", data.display());
+
+    let mut days = vec![];
+    for entry in fs::read_dir(&data).unwrap().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let day = name
+            .strip_prefix("day")
+            .and_then(|name| name.strip_suffix(".txt"))
+            .and_then(|day| day.parse::<usize>().ok());
+        if let Some(day) = day {
+            days.push((day, entry.path()));
+        }
+    }
+    days.sort();
+
+    let mut source = String::from(
+        "/// the input for `day` as it was when the binary was built.\n\
+         pub fn input(day: usize) -> Option<&'static str> {\n    match day {\n",
+    );
+    for (day, path) in days {
+        source += &format!(
+            "        {} => Some(include_str!({:?})),\n",
+            day,
+            path.display().to_string()
+        );
+    }
+    source += "        _ => None,\n    }\n}\n\n";
+
+    let answers = data.join("answers.txt");
+    source += "/// the expected answers as they were when the binary was built.\n";
+    if answers.exists() {
+        source += &format!(
+            "pub const ANSWERS: Option<&str> = Some(include_str!({:?}));\n",
+            answers.display().to_string()
+        );
+    } else {
+        source += "pub const ANSWERS: Option<&str> = None;\n";
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("embedded.rs");
+    fs::write(out, source).unwrap();
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use crate::{
+    io::{data_dir, read_lines},
+    result::Result,
+};
+
+/// expected answers for each day and part, read from lines of `day part answer`.
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: HashMap<(usize, usize), String>,
+}
+
+impl Answers {
+    pub fn read(filename: &str) -> Result<Self> {
+        Self::parse(read_lines(filename)?)
+    }
+
+    /// reads `filename`, or the default answers when no file is given: the ones compiled in with
+    /// the `embed` feature, or `answers.txt` in the data directory.
+    pub fn load(filename: Option<&str>) -> Result<Self> {
+        #[cfg(feature = "embed")]
+        {
+            if let (None, Some(answers)) = (filename, crate::embedded::ANSWERS) {
+                return Self::parse(answers.lines().map(String::from));
+            }
+        }
+
+        match filename {
+            Some(filename) => Self::read(filename),
+            None => Self::read(&format!("{}/answers.txt", data_dir())),
+        }
+    }
+
+    pub fn parse<I: IntoIterator<Item = String>>(lines: I) -> Result<Self> {
+        let mut answers = HashMap::new();
+        for (idx, line) in lines.into_iter().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(3, char::is_whitespace);
+            let day = parts.next().and_then(|d| d.parse().ok());
+            let part = parts.next().and_then(|p| p.parse().ok());
+            let answer = parts.next().map(str::trim);
+            match (day, part, answer) {
+                (Some(day), Some(part), Some(answer)) if !answer.is_empty() => {
+                    answers.insert((day, part), answer.to_string());
+                }
+                _ => {
+                    return Err(format!(
+                        "line {}: expected `day part answer`, found `{}`",
+                        idx + 1,
+                        line
+                    )
+                    .into())
+                }
+            }
+        }
+
+        Ok(Self { answers })
+    }
+
+    pub fn get(&self, day: usize, part: usize) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Answers;
+
+    fn lines(input: &str) -> Vec<String> {
+        input.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(lines("# comment\n1 1 514579\n\n1 2 241861950\n")).unwrap();
+        assert_eq!(Some("514579"), answers.get(1, 1));
+        assert_eq!(Some("241861950"), answers.get(1, 2));
+        assert_eq!(None, answers.get(2, 1));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Answers::parse(lines("1 1")).is_err());
+        assert!(Answers::parse(lines("one 1 2")).is_err());
+    }
+}
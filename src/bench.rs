@@ -0,0 +1,124 @@
+use std::{
+    io::Read,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    registry::{DynPuzzle, SolveContext},
+    result::Result,
+};
+
+/// summary of a set of timing samples.
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort();
+        // nearest-rank percentile, so every reported value is an actual sample
+        let percentile = |p: usize| samples[(samples.len() * p).div_ceil(100).max(1) - 1];
+        Some(Stats {
+            min: samples[0],
+            median: percentile(50),
+            p95: percentile(95),
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+pub struct Report {
+    pub day: usize,
+    pub parse: Stats,
+    pub part_one: Stats,
+    pub part_two: Stats,
+}
+
+/// times parsing and both parts of `puzzle` separately over `iterations` runs.
+pub fn bench(puzzle: &dyn DynPuzzle, filename: Option<&str>, iterations: usize) -> Result<Report> {
+    if iterations == 0 {
+        return Err("at least one iteration is required".into());
+    }
+
+    // stdin can only be read once, so keep it around and parse it from memory every iteration
+    let stdin = match filename {
+        Some("-") => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            Some(input)
+        }
+        _ => None,
+    };
+
+    let mut parse = Vec::with_capacity(iterations);
+    let mut part_one = Vec::with_capacity(iterations);
+    let mut part_two = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let (input, elapsed) = time(|| match &stdin {
+            Some(input) => puzzle.parse_str(input),
+            None => puzzle.parse(filename),
+        })?;
+        parse.push(elapsed);
+        let mut context = SolveContext::default();
+        part_one.push(time(|| puzzle.solve(1, input.as_ref(), &mut context))?.1);
+        part_two.push(time(|| puzzle.solve(2, input.as_ref(), &mut context))?.1);
+    }
+
+    // iterations is non zero, so every sample set has at least one value
+    Ok(Report {
+        day: puzzle.day(),
+        parse: Stats::from_samples(parse).unwrap(),
+        part_one: Stats::from_samples(part_one).unwrap(),
+        part_two: Stats::from_samples(part_two).unwrap(),
+    })
+}
+
+/// runs `f`, returning its output along with how long it took.
+pub fn time<T, F: FnOnce() -> Result<T>>(f: F) -> Result<(T, Duration)> {
+    let start = Instant::now();
+    let out = f()?;
+    Ok((out, start.elapsed()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::Stats;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stats() {
+        let samples = (1..=100).rev().map(Duration::from_millis).collect();
+        assert_eq!(
+            Some(Stats {
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(50),
+                p95: Duration::from_millis(95),
+                max: Duration::from_millis(100),
+            }),
+            Stats::from_samples(samples)
+        );
+    }
+
+    #[test]
+    fn test_stats_single_sample() {
+        let sample = Duration::from_millis(3);
+        let stats = Stats::from_samples(vec![sample]).unwrap();
+        assert_eq!(sample, stats.min);
+        assert_eq!(sample, stats.median);
+        assert_eq!(sample, stats.p95);
+        assert_eq!(sample, stats.max);
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        assert_eq!(None, Stats::from_samples(vec![]));
+    }
+}
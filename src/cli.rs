@@ -0,0 +1,964 @@
+use std::time::Duration;
+
+use aoc2020::{
+    history::{Baseline, HISTORY_FILE},
+    params::Assignment,
+    report::Report,
+    result::Result,
+    run::Format,
+};
+
+pub const USAGE: &str = "usage:
+    aoc2020 run [--all | --day N] [--part 1|2] [--input FILE|-] [--format text|json]
+                [--param NAME=VALUE]... [--config FILE] [--jobs N] [--strategy NAME]
+    aoc2020 bench [--all | --day N] [--input FILE|-] [--iterations N]
+                  [--param NAME=VALUE]... [--config FILE] [--history FILE] [--no-save]
+                  [--strategy NAME]
+    aoc2020 crosscheck [--all | --day N] [--input FILE|-] [--param NAME=VALUE]... [--config FILE]
+    aoc2020 compare [--history FILE] [--baseline REVISION|TIMESTAMP] [--threshold PERCENT]
+    aoc2020 memory [--all | --day N] [--input FILE|-]
+    aoc2020 verify [--all | --day N] [--answers FILE] [--report text|junit|tap]
+    aoc2020 examples [--all | --day N] [--report text|junit|tap]
+    aoc2020 params [--all | --day N]
+    aoc2020 repl --day N [--input FILE]
+    aoc2020 gen --day N [--seed S] [--size M] [--param NAME=VALUE]... [--example]
+    aoc2020 fuzz [--all | --day N] [--seed S] [--iterations N] [--timeout MS]
+    aoc2020 minimize --day N [--input FILE|-] [--part 1|2] [--reference STRATEGY]
+                     [--expected PART=ANSWER] [--param NAME=VALUE]... [--name NAME]
+    aoc2020 new DAY
+    aoc2020 help
+
+parameters can be scoped to a single day with `--param dayN.NAME=VALUE`. `params` lists the
+strategies of each day, and `--strategy` picks one for the selected day. `minimize` shrinks
+an input while the strategies of the day disagree on it, or with `--expected` while the day
+answers the part with something else. `--jobs` runs days in parallel, and the two parts of a
+day run at the same time unless part two is solved from the answer of part one.";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions),
+    Bench(BenchOptions),
+    Compare(CompareOptions),
+    /// runs every strategy of a day and checks that their answers agree
+    Crosscheck(CrosscheckOptions),
+    /// reports allocations, only available with the `count-allocs` feature
+    Memory(MemoryOptions),
+    Verify(VerifyOptions),
+    /// checks the example cases of the given day, or of every day when `None`
+    Examples(ExamplesOptions),
+    /// lists the parameters of the given day, or of every day when `None`
+    Params(Option<usize>),
+    /// parses the input of a day once and answers commands about it
+    Repl(ReplOptions),
+    /// writes a random input for a day, with the answers it was built to have
+    Gen(GenOptions),
+    /// feeds mutated inputs to the parsers, looking for panics and hangs
+    Fuzz(FuzzOptions),
+    /// shrinks an input the strategies of a day disagree on into a new example case
+    Minimize(MinimizeOptions),
+    /// generates the module and input files for a new day
+    New(usize),
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunOptions {
+    /// day to run, or every day when `None`
+    pub day: Option<usize>,
+    /// part to run, or both parts when `None`
+    pub part: Option<usize>,
+    /// input file to use instead of `./data/dayN.txt`, `-` for stdin
+    pub input: Option<String>,
+    pub format: Format,
+    /// parameter assignments, applied after the ones from `config`
+    pub params: Vec<Assignment>,
+    /// file with one parameter assignment per line
+    pub config: Option<String>,
+    /// number of days run at the same time
+    pub jobs: usize,
+    /// strategy to solve the selected day with, instead of its default one
+    pub strategy: Option<String>,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            day: None,
+            part: None,
+            input: None,
+            format: Format::default(),
+            params: vec![],
+            config: None,
+            jobs: 1,
+            strategy: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    /// day to benchmark, or every day when `None`
+    pub day: Option<usize>,
+    /// input file to use instead of `./data/dayN.txt`, `-` for stdin
+    pub input: Option<String>,
+    /// number of times each day is parsed and solved
+    pub iterations: usize,
+    /// parameter assignments, applied after the ones from `config`
+    pub params: Vec<Assignment>,
+    /// file with one parameter assignment per line
+    pub config: Option<String>,
+    /// file the median of every part is recorded in
+    pub history: String,
+    /// whether to record the run in `history`
+    pub save: bool,
+    /// strategy to solve the selected day with, instead of its default one
+    pub strategy: Option<String>,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            day: None,
+            input: None,
+            iterations: 10,
+            params: vec![],
+            config: None,
+            history: HISTORY_FILE.into(),
+            save: true,
+            strategy: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CompareOptions {
+    /// file the benchmark runs were recorded in
+    pub history: String,
+    /// run the latest one is compared against
+    pub baseline: Baseline,
+    /// how many percent slower a part has to get to count as a regression
+    pub threshold: f64,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self {
+            history: HISTORY_FILE.into(),
+            baseline: Baseline::Previous,
+            threshold: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct CrosscheckOptions {
+    /// day to check, or every day with more than one strategy when `None`
+    pub day: Option<usize>,
+    /// input file to use instead of `./data/dayN.txt`, `-` for stdin
+    pub input: Option<String>,
+    /// parameter assignments, applied after the ones from `config`
+    pub params: Vec<Assignment>,
+    /// file with one parameter assignment per line
+    pub config: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct MemoryOptions {
+    /// day to measure, or every day when `None`
+    pub day: Option<usize>,
+    /// input file to use instead of `./data/dayN.txt`, `-` for stdin
+    pub input: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct VerifyOptions {
+    /// day to verify, or every day when `None`
+    pub day: Option<usize>,
+    /// file with the expected answers, instead of the default ones
+    pub answers: Option<String>,
+    pub report: Report,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ExamplesOptions {
+    /// day to check the examples of, or every day when `None`
+    pub day: Option<usize>,
+    pub report: Report,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ReplOptions {
+    pub day: usize,
+    /// input file to use instead of `./data/dayN.txt`
+    pub input: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GenOptions {
+    pub day: usize,
+    pub seed: u64,
+    /// number of records, like lines or passports, to generate
+    pub size: usize,
+    /// parameter assignments the answers are planted for
+    pub params: Vec<Assignment>,
+    /// whether to write an example case, with the answers in its header
+    pub example: bool,
+}
+
+impl Default for GenOptions {
+    fn default() -> Self {
+        Self {
+            day: 0,
+            seed: 0,
+            size: 1000,
+            params: vec![],
+            example: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FuzzOptions {
+    /// day to fuzz, or every day when `None`
+    pub day: Option<usize>,
+    pub seed: u64,
+    /// number of mutated inputs parsed for each day
+    pub iterations: usize,
+    /// how long a parser may take on one input before it counts as hanging
+    pub timeout: Duration,
+}
+
+impl Default for FuzzOptions {
+    fn default() -> Self {
+        Self {
+            day: None,
+            seed: 0,
+            iterations: 1000,
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MinimizeOptions {
+    pub day: usize,
+    /// input file to use instead of `./data/dayN.txt`, `-` for stdin
+    pub input: Option<String>,
+    /// part the strategies have to keep disagreeing on, or either part when `None`
+    pub part: Option<usize>,
+    /// strategy whose answers the example expects, instead of the default one
+    pub reference: Option<String>,
+    /// part along with the answer it should have, to shrink the input against instead of the
+    /// other strategies
+    pub expected: Option<(usize, String)>,
+    pub params: Vec<Assignment>,
+    /// name of the example case written
+    pub name: String,
+}
+
+impl Default for MinimizeOptions {
+    fn default() -> Self {
+        Self {
+            day: 0,
+            input: None,
+            part: None,
+            reference: None,
+            expected: None,
+            params: vec![],
+            name: "minimized".into(),
+        }
+    }
+}
+
+pub fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command> {
+    match args.next().as_deref() {
+        None => Ok(Command::Run(RunOptions::default())),
+        Some("run") => parse_run(args).map(Command::Run),
+        Some("bench") => parse_bench(args).map(Command::Bench),
+        Some("compare") => parse_compare(args).map(Command::Compare),
+        Some("crosscheck") => parse_crosscheck(args).map(Command::Crosscheck),
+        Some("memory") => parse_memory(args).map(Command::Memory),
+        Some("verify") => parse_verify(args).map(Command::Verify),
+        Some("examples") => parse_examples(args).map(Command::Examples),
+        Some("params") => parse_day(args).map(Command::Params),
+        Some("repl") => parse_repl(args).map(Command::Repl),
+        Some("gen") => parse_gen(args).map(Command::Gen),
+        Some("fuzz") => parse_fuzz(args).map(Command::Fuzz),
+        Some("minimize") => parse_minimize(args).map(Command::Minimize),
+        Some("new") => parse_new(args).map(Command::New),
+        Some("help") | Some("--help") | Some("-h") => Ok(Command::Help),
+        Some(other) => Err(format!("unknown command `{}`", other).into()),
+    }
+}
+
+fn parse_run<I: Iterator<Item = String>>(mut args: I) -> Result<RunOptions> {
+    let mut options = RunOptions::default();
+    let mut all = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => options.day = Some(parse_number(&arg, args.next())?),
+            "--part" => options.part = Some(parse_number(&arg, args.next())?),
+            "--input" => options.input = Some(value(&arg, args.next())?),
+            "--format" => options.format = parse_format(&arg, args.next())?,
+            "--param" => options.params.push(value(&arg, args.next())?.parse()?),
+            "--config" => options.config = Some(value(&arg, args.next())?),
+            "--jobs" => options.jobs = parse_number(&arg, args.next())?,
+            "--strategy" => options.strategy = Some(value(&arg, args.next())?),
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+
+    validate_selection(all, options.day, &options.input)?;
+    validate_strategy(options.day, &options.strategy)?;
+    if let Some(part) = options.part {
+        if part != 1 && part != 2 {
+            return Err(format!("invalid part `{}`, expected 1 or 2", part).into());
+        }
+    }
+    if options.jobs == 0 {
+        return Err("`--jobs` must be at least 1".into());
+    }
+
+    Ok(options)
+}
+
+fn parse_bench<I: Iterator<Item = String>>(mut args: I) -> Result<BenchOptions> {
+    let mut options = BenchOptions::default();
+    let mut all = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => options.day = Some(parse_number(&arg, args.next())?),
+            "--input" => options.input = Some(value(&arg, args.next())?),
+            "--iterations" => options.iterations = parse_number(&arg, args.next())?,
+            "--param" => options.params.push(value(&arg, args.next())?.parse()?),
+            "--config" => options.config = Some(value(&arg, args.next())?),
+            "--history" => options.history = value(&arg, args.next())?,
+            "--no-save" => options.save = false,
+            "--strategy" => options.strategy = Some(value(&arg, args.next())?),
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+
+    validate_selection(all, options.day, &options.input)?;
+    validate_strategy(options.day, &options.strategy)?;
+    if options.iterations == 0 {
+        return Err("`--iterations` must be at least 1".into());
+    }
+
+    Ok(options)
+}
+
+fn parse_crosscheck<I: Iterator<Item = String>>(mut args: I) -> Result<CrosscheckOptions> {
+    let mut options = CrosscheckOptions::default();
+    let mut all = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => options.day = Some(parse_number(&arg, args.next())?),
+            "--input" => options.input = Some(value(&arg, args.next())?),
+            "--param" => options.params.push(value(&arg, args.next())?.parse()?),
+            "--config" => options.config = Some(value(&arg, args.next())?),
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+
+    validate_selection(all, options.day, &options.input)?;
+    Ok(options)
+}
+
+fn parse_compare<I: Iterator<Item = String>>(mut args: I) -> Result<CompareOptions> {
+    let mut options = CompareOptions::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--history" => options.history = value(&arg, args.next())?,
+            "--baseline" => options.baseline = Baseline::Run(value(&arg, args.next())?),
+            "--threshold" => {
+                let threshold = value(&arg, args.next())?;
+                options.threshold = match threshold.trim_end_matches('%').parse() {
+                    Ok(threshold) if threshold >= 0.0 => threshold,
+                    _ => {
+                        return Err(format!(
+                            "invalid value `{}` for `{}`, expected a percentage",
+                            threshold, arg
+                        )
+                        .into())
+                    }
+                };
+            }
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_memory<I: Iterator<Item = String>>(mut args: I) -> Result<MemoryOptions> {
+    let mut options = MemoryOptions::default();
+    let mut all = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => options.day = Some(parse_number(&arg, args.next())?),
+            "--input" => options.input = Some(value(&arg, args.next())?),
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+
+    validate_selection(all, options.day, &options.input)?;
+    Ok(options)
+}
+
+fn parse_verify<I: Iterator<Item = String>>(mut args: I) -> Result<VerifyOptions> {
+    let mut options = VerifyOptions::default();
+    let mut all = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => options.day = Some(parse_number(&arg, args.next())?),
+            "--answers" => options.answers = Some(value(&arg, args.next())?),
+            "--report" => options.report = parse_report(&arg, args.next())?,
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+
+    validate_selection(all, options.day, &None)?;
+    Ok(options)
+}
+
+fn parse_examples<I: Iterator<Item = String>>(mut args: I) -> Result<ExamplesOptions> {
+    let mut options = ExamplesOptions::default();
+    let mut all = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => options.day = Some(parse_number(&arg, args.next())?),
+            "--report" => options.report = parse_report(&arg, args.next())?,
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+
+    validate_selection(all, options.day, &None)?;
+    Ok(options)
+}
+
+fn parse_day<I: Iterator<Item = String>>(mut args: I) -> Result<Option<usize>> {
+    let mut day = None;
+    let mut all = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => day = Some(parse_number(&arg, args.next())?),
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+
+    validate_selection(all, day, &None)?;
+    Ok(day)
+}
+
+fn parse_repl<I: Iterator<Item = String>>(mut args: I) -> Result<ReplOptions> {
+    let mut day = None;
+    let mut input = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(&arg, args.next())?),
+            "--input" => input = Some(value(&arg, args.next())?),
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+
+    let day = day.ok_or("`repl` requires `--day`")?;
+    Ok(ReplOptions { day, input })
+}
+
+fn parse_gen<I: Iterator<Item = String>>(mut args: I) -> Result<GenOptions> {
+    let mut options = GenOptions::default();
+    let mut day = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(&arg, args.next())?),
+            "--seed" => options.seed = parse_number(&arg, args.next())? as u64,
+            "--size" => options.size = parse_number(&arg, args.next())?,
+            "--param" => options.params.push(value(&arg, args.next())?.parse()?),
+            "--example" => options.example = true,
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+
+    options.day = day.ok_or("`gen` requires `--day`")?;
+    if options.size == 0 {
+        return Err("`--size` must be at least 1".into());
+    }
+    Ok(options)
+}
+
+fn parse_fuzz<I: Iterator<Item = String>>(mut args: I) -> Result<FuzzOptions> {
+    let mut options = FuzzOptions::default();
+    let mut all = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => options.day = Some(parse_number(&arg, args.next())?),
+            "--seed" => options.seed = parse_number(&arg, args.next())? as u64,
+            "--iterations" => options.iterations = parse_number(&arg, args.next())?,
+            "--timeout" => {
+                options.timeout = Duration::from_millis(parse_number(&arg, args.next())? as u64)
+            }
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+
+    validate_selection(all, options.day, &None)?;
+    if options.timeout.is_zero() {
+        return Err("`--timeout` must be at least 1".into());
+    }
+    Ok(options)
+}
+
+fn parse_minimize<I: Iterator<Item = String>>(mut args: I) -> Result<MinimizeOptions> {
+    let mut options = MinimizeOptions::default();
+    let mut day = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(&arg, args.next())?),
+            "--input" => options.input = Some(value(&arg, args.next())?),
+            "--part" => options.part = Some(parse_number(&arg, args.next())?),
+            "--reference" => options.reference = Some(value(&arg, args.next())?),
+            "--expected" => {
+                let expected = value(&arg, args.next())?;
+                let (part, answer) = expected.split_once('=').ok_or_else(|| {
+                    format!("invalid `--expected` `{}`, expected PART=ANSWER", expected)
+                })?;
+                options.expected = Some((parse_number(&arg, Some(part.into()))?, answer.into()));
+            }
+            "--param" => options.params.push(value(&arg, args.next())?.parse()?),
+            "--name" => options.name = value(&arg, args.next())?,
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        }
+    }
+
+    options.day = day.ok_or("`minimize` requires `--day`")?;
+    if options.expected.is_some() && (options.part.is_some() || options.reference.is_some()) {
+        return Err("`--expected` cannot be used with `--part` or `--reference`".into());
+    }
+    let part = options
+        .part
+        .or(options.expected.as_ref().map(|(part, _)| *part));
+    if let Some(part) = part {
+        if part != 1 && part != 2 {
+            return Err(format!("invalid part `{}`, expected 1 or 2", part).into());
+        }
+    }
+    // the name becomes a file name in the examples directory, so it must not be a path
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    if options.name.is_empty() || !options.name.chars().all(valid) {
+        return Err(format!(
+            "invalid example name `{}`, expected letters, digits, `_` and `-`",
+            options.name
+        )
+        .into());
+    }
+    Ok(options)
+}
+
+fn parse_new<I: Iterator<Item = String>>(mut args: I) -> Result<usize> {
+    let day = parse_number("new", args.next())?;
+    if let Some(arg) = args.next() {
+        return Err(format!("unknown argument `{}`", arg).into());
+    }
+    Ok(day)
+}
+
+fn validate_selection(all: bool, day: Option<usize>, input: &Option<String>) -> Result<()> {
+    if all && day.is_some() {
+        return Err("`--all` and `--day` cannot be used together".into());
+    }
+    if day.is_none() && input.is_some() {
+        return Err("`--input` requires `--day`".into());
+    }
+    Ok(())
+}
+
+fn validate_strategy(day: Option<usize>, strategy: &Option<String>) -> Result<()> {
+    if day.is_none() && strategy.is_some() {
+        return Err("`--strategy` requires `--day`, as strategies belong to a single day".into());
+    }
+    Ok(())
+}
+
+fn parse_format(flag: &str, val: Option<String>) -> Result<Format> {
+    match value(flag, val)?.as_str() {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        other => Err(format!("invalid format `{}`, expected text or json", other).into()),
+    }
+}
+
+fn parse_report(flag: &str, val: Option<String>) -> Result<Report> {
+    match value(flag, val)?.as_str() {
+        "text" => Ok(Report::Text),
+        "junit" => Ok(Report::Junit),
+        "tap" => Ok(Report::Tap),
+        other => Err(format!("invalid report `{}`, expected text, junit or tap", other).into()),
+    }
+}
+
+fn value(flag: &str, value: Option<String>) -> Result<String> {
+    value.ok_or_else(|| format!("missing value for `{}`", flag).into())
+}
+
+fn parse_number(flag: &str, val: Option<String>) -> Result<usize> {
+    let val = value(flag, val)?;
+    val.parse()
+        .map_err(|_| format!("invalid value `{}` for `{}`", val, flag).into())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use aoc2020::history::Baseline;
+
+    use super::{
+        parse_args, BenchOptions, Command, CompareOptions, CrosscheckOptions, ExamplesOptions,
+        Format, FuzzOptions, GenOptions, MemoryOptions, MinimizeOptions, ReplOptions, Report,
+        RunOptions, VerifyOptions,
+    };
+
+    fn parse(args: &[&str]) -> Command {
+        parse_args(args.iter().map(|a| a.to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_parse_run() {
+        assert_eq!(Command::Run(RunOptions::default()), parse(&[]));
+        assert_eq!(
+            Command::Run(RunOptions::default()),
+            parse(&["run", "--all"])
+        );
+        assert_eq!(
+            Command::Run(RunOptions {
+                day: Some(7),
+                part: Some(2),
+                input: Some("in.txt".into()),
+                format: Format::Text,
+                ..Default::default()
+            }),
+            parse(&["run", "--day", "7", "--part", "2", "--input", "in.txt"])
+        );
+        assert_eq!(
+            Command::Run(RunOptions {
+                format: Format::Json,
+                ..Default::default()
+            }),
+            parse(&["run", "--format", "json"])
+        );
+        assert_eq!(
+            Command::Run(RunOptions {
+                jobs: 4,
+                ..Default::default()
+            }),
+            parse(&["run", "--jobs", "4"])
+        );
+        assert_eq!(
+            Command::Run(RunOptions {
+                day: Some(7),
+                params: vec![
+                    "color=dark red".parse().unwrap(),
+                    "day7.color=bright white".parse().unwrap()
+                ],
+                config: Some("params.txt".into()),
+                ..Default::default()
+            }),
+            parse(&[
+                "run",
+                "--day",
+                "7",
+                "--param",
+                "color=dark red",
+                "--param",
+                "day7.color=bright white",
+                "--config",
+                "params.txt"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        assert_eq!(
+            Command::Bench(BenchOptions::default()),
+            parse(&["bench", "--all"])
+        );
+        assert_eq!(
+            Command::Bench(BenchOptions {
+                day: Some(9),
+                iterations: 100,
+                ..Default::default()
+            }),
+            parse(&["bench", "--day", "9", "--iterations", "100"])
+        );
+        assert_eq!(
+            Command::Bench(BenchOptions {
+                history: "history.txt".into(),
+                save: false,
+                ..Default::default()
+            }),
+            parse(&["bench", "--history", "history.txt", "--no-save"])
+        );
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        assert_eq!(
+            Command::Compare(CompareOptions::default()),
+            parse(&["compare"])
+        );
+        assert_eq!(
+            Command::Compare(CompareOptions {
+                baseline: Baseline::Run("4a1b2c3".into()),
+                threshold: 5.0,
+                ..Default::default()
+            }),
+            parse(&["compare", "--baseline", "4a1b2c3", "--threshold", "5%"])
+        );
+    }
+
+    #[test]
+    fn test_parse_memory() {
+        assert_eq!(
+            Command::Memory(MemoryOptions {
+                day: Some(8),
+                input: Some("-".into()),
+            }),
+            parse(&["memory", "--day", "8", "--input", "-"])
+        );
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        assert_eq!(
+            Command::Verify(VerifyOptions::default()),
+            parse(&["verify"])
+        );
+        assert_eq!(
+            Command::Verify(VerifyOptions {
+                day: Some(4),
+                answers: Some("answers.txt".into()),
+                ..Default::default()
+            }),
+            parse(&["verify", "--day", "4", "--answers", "answers.txt"])
+        );
+    }
+
+    #[test]
+    fn test_parse_params() {
+        assert_eq!(Command::Params(None), parse(&["params"]));
+        assert_eq!(Command::Params(Some(3)), parse(&["params", "--day", "3"]));
+        assert_eq!(
+            Command::Examples(ExamplesOptions::default()),
+            parse(&["examples", "--all"])
+        );
+        assert_eq!(
+            Command::Examples(ExamplesOptions {
+                day: Some(4),
+                report: Report::Tap,
+            }),
+            parse(&["examples", "--day", "4", "--report", "tap"])
+        );
+    }
+
+    #[test]
+    fn test_parse_strategy() {
+        assert_eq!(
+            Command::Run(RunOptions {
+                day: Some(1),
+                strategy: Some("hash-set".into()),
+                ..Default::default()
+            }),
+            parse(&["run", "--day", "1", "--strategy", "hash-set"])
+        );
+        assert_eq!(
+            Command::Bench(BenchOptions {
+                day: Some(7),
+                strategy: Some("memoized".into()),
+                ..Default::default()
+            }),
+            parse(&["bench", "--day", "7", "--strategy", "memoized"])
+        );
+        assert_eq!(
+            Command::Crosscheck(CrosscheckOptions {
+                day: Some(7),
+                params: vec!["color=dark olive".parse().unwrap()],
+                ..Default::default()
+            }),
+            parse(&["crosscheck", "--day", "7", "--param", "color=dark olive"])
+        );
+        let parse = |args: &[&str]| parse_args(args.iter().map(|a| a.to_string()));
+        assert!(parse(&["run", "--strategy", "hash-set"]).is_err());
+        assert!(parse(&["crosscheck", "--strategy", "hash-set"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_repl() {
+        assert_eq!(
+            Command::Repl(ReplOptions {
+                day: 8,
+                input: Some("in.txt".into()),
+            }),
+            parse(&["repl", "--day", "8", "--input", "in.txt"])
+        );
+        let parse = |args: &[&str]| parse_args(args.iter().map(|a| a.to_string()));
+        assert!(parse(&["repl"]).is_err());
+        assert!(parse(&["repl", "--all"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_gen() {
+        assert_eq!(
+            Command::Gen(GenOptions {
+                day: 9,
+                seed: 7,
+                size: 500,
+                params: vec!["preamble=5".parse().unwrap()],
+                example: true,
+            }),
+            parse(&[
+                "gen",
+                "--day",
+                "9",
+                "--seed",
+                "7",
+                "--size",
+                "500",
+                "--param",
+                "preamble=5",
+                "--example"
+            ])
+        );
+        let parse = |args: &[&str]| parse_args(args.iter().map(|a| a.to_string()));
+        assert!(parse(&["gen", "--seed", "7"]).is_err());
+        assert!(parse(&["gen", "--day", "1", "--size", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_fuzz() {
+        assert_eq!(
+            Command::Fuzz(FuzzOptions::default()),
+            parse(&["fuzz", "--all"])
+        );
+        assert_eq!(
+            Command::Fuzz(FuzzOptions {
+                day: Some(4),
+                seed: 3,
+                iterations: 50,
+                timeout: Duration::from_millis(250),
+            }),
+            parse(&[
+                "fuzz",
+                "--day",
+                "4",
+                "--seed",
+                "3",
+                "--iterations",
+                "50",
+                "--timeout",
+                "250"
+            ])
+        );
+        let parse = |args: &[&str]| parse_args(args.iter().map(|a| a.to_string()));
+        assert!(parse(&["fuzz", "--timeout", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_minimize() {
+        assert_eq!(
+            Command::Minimize(MinimizeOptions {
+                day: 1,
+                ..Default::default()
+            }),
+            parse(&["minimize", "--day", "1"])
+        );
+        assert_eq!(
+            Command::Minimize(MinimizeOptions {
+                day: 7,
+                input: Some("-".into()),
+                part: Some(2),
+                reference: Some("recursive".into()),
+                params: vec!["color=dark olive".parse().unwrap()],
+                name: "nested".into(),
+                ..Default::default()
+            }),
+            parse(&[
+                "minimize",
+                "--day",
+                "7",
+                "--input",
+                "-",
+                "--part",
+                "2",
+                "--reference",
+                "recursive",
+                "--param",
+                "color=dark olive",
+                "--name",
+                "nested"
+            ])
+        );
+        let parse = |args: &[&str]| parse_args(args.iter().map(|a| a.to_string()));
+        assert!(parse(&["minimize"]).is_err());
+        assert!(parse(&["minimize", "--day", "1", "--part", "3"]).is_err());
+        assert_eq!(
+            Command::Minimize(MinimizeOptions {
+                day: 9,
+                expected: Some((2, "62".into())),
+                ..Default::default()
+            }),
+            parse(&["minimize", "--day", "9", "--expected", "2=62"]).unwrap()
+        );
+        for expected in ["62", "3=62", "two=62"] {
+            assert!(parse(&["minimize", "--day", "9", "--expected", expected]).is_err());
+        }
+        assert!(parse(&[
+            "minimize",
+            "--day",
+            "9",
+            "--expected",
+            "2=62",
+            "--part",
+            "2"
+        ])
+        .is_err());
+        for name in ["../day2/x", "..", ".", "", "a b", "x\\y"] {
+            assert!(parse(&["minimize", "--day", "1", "--name", name]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_new() {
+        assert_eq!(Command::New(10), parse(&["new", "10"]));
+    }
+
+    #[test]
+    fn test_parse_run_errors() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|a| a.to_string()));
+        assert!(parse(&["run", "--day"]).is_err());
+        assert!(parse(&["run", "--day", "x"]).is_err());
+        assert!(parse(&["run", "--part", "3"]).is_err());
+        assert!(parse(&["run", "--all", "--day", "1"]).is_err());
+        assert!(parse(&["run", "--input", "in.txt"]).is_err());
+        assert!(parse(&["bench", "--iterations", "0"]).is_err());
+        assert!(parse(&["bench", "--part", "1"]).is_err());
+        assert!(parse(&["verify", "--input", "in.txt"]).is_err());
+        assert!(parse(&["run", "--format", "xml"]).is_err());
+        assert!(parse(&["run", "--param", "target"]).is_err());
+        assert!(parse(&["run", "--jobs", "0"]).is_err());
+        assert!(parse(&["params", "--input", "in.txt"]).is_err());
+        assert!(parse(&["new"]).is_err());
+        assert!(parse(&["verify", "--report", "xml"]).is_err());
+        assert!(parse(&["compare", "--threshold", "-5"]).is_err());
+        assert!(parse(&["new", "10", "11"]).is_err());
+        assert!(parse(&["frobnicate"]).is_err());
+    }
+}
@@ -1,6 +1,35 @@
-use crate::io::read_lines;
+use std::collections::HashSet;
 
-pub struct Puzzle;
+use crate::{
+    gen::{Generated, Rng, ATTEMPTS},
+    io::{parse_lines, write_lines},
+    params::{parse_value, unknown_param, Param},
+    result::{Error, ParseError},
+    strategy::{unknown_strategy, Strategy},
+};
+
+pub struct Puzzle {
+    /// value the entries have to sum to
+    target: i64,
+    solver: Solver,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Solver {
+    /// tries every combination of entries
+    BruteForce,
+    /// looks the last entry of a combination up in a set
+    HashSet,
+}
+
+impl Default for Puzzle {
+    fn default() -> Self {
+        Self {
+            target: 2020,
+            solver: Solver::BruteForce,
+        }
+    }
+}
 
 impl crate::PuzzleRunner for Puzzle {
     const DAY: usize = 1;
@@ -8,37 +37,260 @@ impl crate::PuzzleRunner for Puzzle {
     type Second = i64;
     type Input = Vec<i64>;
 
-    fn parse_input(&self, filename: &str) -> crate::result::Result<Self::Input> {
-        let lines: Vec<i64> = read_lines(filename)?
-            .into_iter()
-            .map(|n| n.parse().unwrap())
-            .collect();
-        Ok(lines)
+    fn parse_str(&self, input: &str) -> crate::result::Result<Self::Input> {
+        parse_lines(Self::DAY, input, |n| {
+            n.parse().map_err(|_| {
+                ParseError::within(n, n, "invalid number").with_hint("expected an integer")
+            })
+        })
     }
 
-    fn part_one(&self, entries: &Self::Input) -> Self::First {
-        for i in entries.iter() {
-            for j in entries.iter() {
-                if i + j == 2020 {
-                    return i * j;
-                }
+    fn part_one(&self, entries: &Self::Input) -> crate::result::Result<Self::First> {
+        let pair = match self.solver {
+            Solver::BruteForce => pair_brute_force(entries, self.target),
+            Solver::HashSet => pair_hash_set(entries, self.target),
+        };
+        product(
+            &pair.ok_or_else(|| {
+                Error::NoSolution(format!("no two entries sum to {}", self.target))
+            })?,
+        )
+    }
+
+    fn part_two(
+        &self,
+        entries: &Self::Input,
+        _first: Option<&Self::First>,
+    ) -> crate::result::Result<Self::Second> {
+        let triple = match self.solver {
+            Solver::BruteForce => triple_brute_force(entries, self.target),
+            Solver::HashSet => triple_hash_set(entries, self.target),
+        };
+        product(
+            &triple.ok_or_else(|| {
+                Error::NoSolution(format!("no three entries sum to {}", self.target))
+            })?,
+        )
+    }
+
+    fn params(&self) -> Vec<Param> {
+        vec![Param::new(
+            "target",
+            "value the entries have to sum to",
+            self.target,
+        )]
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> crate::result::Result<()> {
+        match name {
+            "target" => self.target = parse_value(name, value, "an integer")?,
+            _ => return Err(unknown_param(Self::DAY, name)),
+        }
+        Ok(())
+    }
+
+    fn write_input(&self, entries: &Self::Input) -> crate::result::Result<String> {
+        Ok(write_lines(entries))
+    }
+
+    /// plants a pair and a triple below the target, and fills the rest of the list with entries
+    /// above it, which cannot be part of any sum.
+    fn generate(&self, rng: &mut Rng, size: usize) -> crate::result::Result<Generated> {
+        let target = self.target;
+        if !(12..i64::MAX / 2).contains(&target) {
+            return Err(format!("cannot plant entries summing to {}", target).into());
+        }
+
+        let (pair, triple) = (0..ATTEMPTS)
+            .map(|_| {
+                let a = rng.between(1, target - 1);
+                let c = rng.between(1, target - 2);
+                let d = rng.between(1, target - c - 1);
+                ([a, target - a], [c, d, target - c - d])
+            })
+            .find(|(pair, triple)| planted_once(pair, triple, target))
+            .ok_or("could not plant a single pair and triple")?;
+
+        let mut entries: Vec<i64> = pair.iter().chain(triple.iter()).copied().collect();
+        while entries.len() < size {
+            entries.push(rng.between(target + 1, target * 2));
+        }
+        rng.shuffle(&mut entries);
+
+        let input: Vec<String> = entries.iter().map(i64::to_string).collect();
+        Ok(Generated::new(
+            input.join("\n") + "\n",
+            product(&pair)?,
+            product(&triple)?,
+        ))
+    }
+
+    fn strategies(&self) -> Vec<Strategy> {
+        vec![
+            Strategy::new("brute-force", "try every combination of entries"),
+            Strategy::new("hash-set", "look the last entry up in a set of all entries"),
+        ]
+    }
+
+    fn set_strategy(&mut self, name: &str) -> crate::result::Result<()> {
+        self.solver = match name {
+            "brute-force" => Solver::BruteForce,
+            "hash-set" => Solver::HashSet,
+            _ => return Err(unknown_strategy(Self::DAY, name)),
+        };
+        Ok(())
+    }
+}
+
+/// multiplies the entries that sum to the target, failing rather than overflowing.
+fn product(entries: &[i64]) -> crate::result::Result<i64> {
+    entries
+        .iter()
+        .try_fold(1i64, |product, entry| product.checked_mul(*entry))
+        .ok_or_else(|| format!("the product of {:?} overflows", entries).into())
+}
+
+// sums that overflow cannot be the target, so `checked_add` and `checked_sub` skip them
+
+fn pair_brute_force(entries: &[i64], target: i64) -> Option<[i64; 2]> {
+    for i in entries.iter() {
+        for j in entries.iter() {
+            if i.checked_add(*j) == Some(target) {
+                return Some([*i, *j]);
             }
         }
+    }
+    None
+}
 
-        return 0;
+fn pair_hash_set(entries: &[i64], target: i64) -> Option<[i64; 2]> {
+    let set: HashSet<i64> = entries.iter().copied().collect();
+    for i in entries.iter() {
+        match target.checked_sub(*i) {
+            Some(j) if set.contains(&j) => return Some([*i, j]),
+            _ => {}
+        }
     }
+    None
+}
 
-    fn part_two(&self, entries: &Self::Input) -> Self::Second {
-        for i in entries.iter() {
-            for j in entries.iter() {
-                for k in entries.iter() {
-                    if i + j + k == 2020 {
-                        return i * j * k;
-                    }
+fn triple_brute_force(entries: &[i64], target: i64) -> Option<[i64; 3]> {
+    for i in entries.iter() {
+        for j in entries.iter() {
+            for k in entries.iter() {
+                if i.checked_add(*j).and_then(|sum| sum.checked_add(*k)) == Some(target) {
+                    return Some([*i, *j, *k]);
                 }
             }
         }
+    }
+    None
+}
+
+/// finds the same triple as `triple_brute_force`, as both go through the first two entries in
+/// the same order.
+fn triple_hash_set(entries: &[i64], target: i64) -> Option<[i64; 3]> {
+    let set: HashSet<i64> = entries.iter().copied().collect();
+    for i in entries.iter() {
+        for j in entries.iter() {
+            match target.checked_sub(*i).and_then(|rest| rest.checked_sub(*j)) {
+                Some(k) if set.contains(&k) => return Some([*i, *j, k]),
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+/// whether `pair` is the only pair and `triple` the only triple of the planted entries summing
+/// to `target`. The parts may use the same entry more than once, so the combinations do too.
+fn planted_once(pair: &[i64; 2], triple: &[i64; 3], target: i64) -> bool {
+    let mut entries: Vec<i64> = pair.iter().chain(triple.iter()).copied().collect();
+    entries.sort_unstable();
+    if entries.windows(2).any(|w| w[0] == w[1]) {
+        return false;
+    }
+
+    let mut pairs = 0;
+    let mut triples = 0;
+    for (i, a) in entries.iter().enumerate() {
+        for (j, b) in entries.iter().enumerate().skip(i) {
+            pairs += (a + b == target) as usize;
+            for c in entries.iter().skip(j) {
+                triples += (a + b + c == target) as usize;
+            }
+        }
+    }
+    pairs == 1 && triples == 1
+}
+
+#[cfg(test)]
+mod test {
+    use super::Puzzle;
+    use crate::{
+        gen::{assert_round_trip, Rng},
+        PuzzleRunner,
+    };
+
+    const EXAMPLE: &str = "1721\n979\n366\n299\n675\n1456\n";
+
+    #[test]
+    fn test_part_one() {
+        let puzzle = Puzzle::default();
+        let entries = puzzle.parse_str(EXAMPLE).unwrap();
+        assert_eq!(514579, puzzle.part_one(&entries).unwrap());
+    }
+
+    #[test]
+    fn test_part_two() {
+        let puzzle = Puzzle::default();
+        let entries = puzzle.parse_reader(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(241861950, puzzle.part_two(&entries, None).unwrap());
+    }
+
+    #[test]
+    fn test_target_param() {
+        let mut puzzle = Puzzle::default();
+        puzzle.set_param("target", "2700").unwrap();
+        let entries = puzzle.parse_str(EXAMPLE).unwrap();
+        assert_eq!(1721 * 979, puzzle.part_one(&entries).unwrap());
+        assert!(puzzle.set_param("target", "lots").is_err());
+        assert!(puzzle.set_param("preamble", "5").is_err());
+    }
+
+    #[test]
+    fn test_hash_set_strategy() {
+        let mut puzzle = Puzzle::default();
+        puzzle.set_strategy("hash-set").unwrap();
+        let entries = puzzle.parse_str(EXAMPLE).unwrap();
+        let first = puzzle.part_one(&entries).unwrap();
+        assert_eq!(514579, first);
+        assert_eq!(241861950, puzzle.part_two(&entries, Some(&first)).unwrap());
+        assert!(puzzle.set_strategy("bogo").is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut puzzle = Puzzle::default();
+        let entries = puzzle
+            .parse_str("9223372036854775807\n2\n-9223372036854775808\n")
+            .unwrap();
+        assert!(puzzle.part_one(&entries).is_err());
+        assert!(puzzle.part_two(&entries, None).is_err());
+
+        puzzle.set_param("target", "9000000000").unwrap();
+        let entries = puzzle.parse_str("4000000000\n5000000000\n").unwrap();
+        for strategy in ["brute-force", "hash-set"] {
+            puzzle.set_strategy(strategy).unwrap();
+            assert!(puzzle.part_one(&entries).is_err());
+        }
+        assert!(puzzle.generate(&mut Rng::new(1), 10).is_err());
+    }
 
-        return 0;
+    #[test]
+    fn test_round_trip() {
+        let puzzle = Puzzle::default();
+        assert_eq!("1721\n-3\n", puzzle.write_input(&vec![1721, -3]).unwrap());
+        assert_round_trip(&puzzle);
     }
 }
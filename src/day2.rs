@@ -1,6 +1,12 @@
-use crate::{io::read_lines, result::Result};
+use std::{fmt, str::FromStr};
 
-#[derive(Debug)]
+use crate::{
+    gen::{Generated, Rng},
+    io::{parse_lines, write_lines},
+    result::{ParseError, Result},
+};
+
+#[derive(Debug, PartialEq)]
 pub struct Entry {
     num_left: usize,
     num_right: usize,
@@ -8,21 +14,74 @@ pub struct Entry {
     password: String,
 }
 
-impl From<String> for Entry {
-    fn from(input: String) -> Self {
+impl FromStr for Entry {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> std::result::Result<Self, Self::Err> {
         let mut split = input.split_whitespace();
-        let mut minmax = split.next().unwrap().split('-');
-        let num_left = minmax.next().unwrap().parse().unwrap();
-        let num_right = minmax.next().unwrap().parse().unwrap();
-        let ch = split.next().unwrap().chars().next().unwrap();
-        let password = split.next().unwrap().to_string();
+        let policy = split
+            .next()
+            .ok_or_else(|| ParseError::end_of(input, "expected a policy like `1-3`"))?;
+        let (left, right) = policy
+            .split_once('-')
+            .ok_or_else(|| ParseError::within(input, policy, "expected a policy like `1-3`"))?;
+        let num_left = parse_position(input, left)?;
+        let num_right = parse_position(input, right)?;
+
+        let letter = split
+            .next()
+            .ok_or_else(|| ParseError::end_of(input, "expected a letter followed by `:`"))?;
+        let mut chars = letter.chars();
+        let ch = match (chars.next(), chars.next(), chars.next()) {
+            (Some(ch), Some(':'), None) => ch,
+            _ => {
+                return Err(ParseError::within(
+                    input,
+                    letter,
+                    "expected a letter followed by `:`",
+                ))
+            }
+        };
+
+        let password = split
+            .next()
+            .ok_or_else(|| ParseError::end_of(input, "expected a password"))?
+            .to_string();
+        if let Some(extra) = split.next() {
+            return Err(ParseError::within(
+                input,
+                extra,
+                "unexpected text after the password",
+            ));
+        }
 
-        Entry {
+        Ok(Entry {
             num_left,
             num_right,
             ch,
             password,
-        }
+        })
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{} {}: {}",
+            self.num_left, self.num_right, self.ch, self.password
+        )
+    }
+}
+
+fn parse_position(line: &str, position: &str) -> std::result::Result<usize, ParseError> {
+    match position.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(ParseError::within(
+            line,
+            position,
+            "expected a position starting at 1",
+        )),
     }
 }
 
@@ -34,50 +93,162 @@ impl crate::PuzzleRunner for Puzzle {
     type Second = usize;
     type Input = Vec<Entry>;
 
-    fn parse_input(&self, filename: &str) -> Result<Self::Input> {
-        let entries = read_lines(filename)?.into_iter().map(Into::into).collect();
-        Ok(entries)
+    fn parse_str(&self, input: &str) -> Result<Self::Input> {
+        parse_lines(Self::DAY, input, str::parse)
     }
 
-    fn part_one(&self, entries: &Self::Input) -> Self::First {
-        entries
-            .into_iter()
+    fn part_one(&self, entries: &Self::Input) -> Result<Self::First> {
+        Ok(entries
+            .iter()
             .filter(|e| {
                 let occurrences = e.password.matches(e.ch).count();
                 occurrences >= e.num_left && occurrences <= e.num_right
             })
-            .count()
+            .count())
     }
 
-    fn part_two(&self, entries: &Self::Input) -> Self::Second {
-        entries
-            .into_iter()
+    fn part_two(
+        &self,
+        entries: &Self::Input,
+        _first: Option<&Self::First>,
+    ) -> Result<Self::Second> {
+        Ok(entries
+            .iter()
             .filter(|e| {
                 let chars: Vec<char> = e.password.chars().collect();
                 let first = chars.get(e.num_left - 1);
                 let second = chars.get(e.num_right - 1);
                 (first == Some(&e.ch) || second == Some(&e.ch)) && first != second
             })
-            .count()
+            .count())
+    }
+
+    fn write_input(&self, entries: &Self::Input) -> Result<String> {
+        Ok(write_lines(entries))
+    }
+
+    /// decides up front which policies each password follows, then builds a password that does.
+    fn generate(&self, rng: &mut Rng, size: usize) -> Result<Generated> {
+        let mut lines = vec![];
+        let (mut first, mut second) = (0, 0);
+        while lines.len() < size {
+            let valid_count = rng.chance(50);
+            let valid_positions = rng.chance(50);
+            if let Some(line) = password_line(rng, valid_count, valid_positions) {
+                lines.push(line);
+                first += valid_count as usize;
+                second += valid_positions as usize;
+            }
+        }
+        Ok(Generated::new(lines.join("\n") + "\n", first, second))
     }
 }
 
+/// a random line whose password follows the count policy when `valid_count` is set and the
+/// position policy when `valid_positions` is, or `None` when the random policy cannot be met.
+fn password_line(rng: &mut Rng, valid_count: bool, valid_positions: bool) -> Option<String> {
+    let left = rng.between(1, 8) as usize;
+    let right = rng.between(left as i64 + 1, left as i64 + 8) as usize;
+    let len = rng.between(right as i64, right as i64 + 6) as usize;
+
+    // how many of the two positions hold the letter
+    let at_positions = if valid_positions {
+        1
+    } else {
+        *rng.choose(&[0, 2])
+    };
+    let counts: Vec<usize> = (0..=len)
+        .filter(|count| (left..=right).contains(count) == valid_count)
+        .filter(|count| (at_positions..=at_positions + len - 2).contains(count))
+        .collect();
+    if counts.is_empty() {
+        return None;
+    }
+    let count = *rng.choose(&counts);
+
+    let mut holds = vec![false; len];
+    match at_positions {
+        1 if rng.chance(50) => holds[left - 1] = true,
+        1 => holds[right - 1] = true,
+        2 => {
+            holds[left - 1] = true;
+            holds[right - 1] = true;
+        }
+        _ => {}
+    }
+    let mut others: Vec<usize> = (0..len)
+        .filter(|idx| *idx != left - 1 && *idx != right - 1)
+        .collect();
+    rng.shuffle(&mut others);
+    for idx in others.into_iter().take(count - at_positions) {
+        holds[idx] = true;
+    }
+
+    let letter = (b'a' + rng.index(26) as u8) as char;
+    let password: String = holds
+        .iter()
+        .map(|holds| match holds {
+            true => letter,
+            false => (b'a' + (letter as u8 - b'a' + 1 + rng.index(25) as u8) % 26) as char,
+        })
+        .collect();
+    Some(format!("{}-{} {}: {}", left, right, letter, password))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::PuzzleRunner;
+    use crate::{gen::assert_round_trip, PuzzleRunner};
+
+    #[test]
+    fn test_parse_entry() {
+        assert_eq!(
+            Entry {
+                num_left: 1,
+                num_right: 3,
+                ch: 'a',
+                password: "abcde".into(),
+            },
+            "1-3 a: abcde".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_entry_errors() {
+        let err = |input: &str| input.parse::<Entry>().unwrap_err();
+        assert_eq!(
+            "1: expected a policy like `1-3`",
+            err("13 a: abcde").to_string()
+        );
+        assert_eq!(
+            "3: expected a position starting at 1",
+            err("1-0 a: abcde").to_string()
+        );
+        assert_eq!(
+            "5: expected a letter followed by `:`",
+            err("1-3 a abcde").to_string()
+        );
+        assert_eq!("7: expected a password", err("1-3 a:").to_string());
+    }
 
     #[test]
     fn test_part_one() {
         let puzzle = Puzzle;
         let entries = puzzle.test_input().unwrap();
-        assert_eq!(2, puzzle.part_one(&entries));
+        assert_eq!(2, puzzle.part_one(&entries).unwrap());
     }
 
     #[test]
     fn test_part_two() {
         let puzzle = Puzzle;
         let entries = puzzle.test_input().unwrap();
-        assert_eq!(1, puzzle.part_two(&entries));
+        assert_eq!(1, puzzle.part_two(&entries, None).unwrap());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let line = "1-3 a: abcde";
+        assert_eq!(line, line.parse::<Entry>().unwrap().to_string());
+        assert_round_trip(&Puzzle);
     }
 }
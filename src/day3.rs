@@ -1,11 +1,52 @@
-use crate::io::read_lines;
-use crate::result::Result;
+use std::{convert::TryFrom, fmt};
 
+use crate::gen::{Generated, Rng};
+use crate::io::parse_lines;
+use crate::params::{invalid_value, parse_value, unknown_param, Param};
+use crate::result::{ParseError, Result};
+
+#[derive(Debug, PartialEq)]
 enum Cell {
     Tree,
     OpenSquare,
 }
 
+impl TryFrom<char> for Cell {
+    type Error = String;
+
+    fn try_from(c: char) -> std::result::Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Cell::OpenSquare),
+            '#' => Ok(Cell::Tree),
+            _ => Err(format!("unexpected `{}`", c)),
+        }
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cell::Tree => write!(f, "#"),
+            Cell::OpenSquare => write!(f, "."),
+        }
+    }
+}
+
+fn parse_row(line: &str) -> std::result::Result<Vec<Cell>, ParseError> {
+    if line.is_empty() {
+        return Err(ParseError::new(1, "expected a row of `.` and `#`"));
+    }
+
+    line.chars()
+        .enumerate()
+        .map(|(idx, c)| {
+            Cell::try_from(c)
+                .map_err(|e| ParseError::new(idx + 1, e).with_hint("expected `.` or `#`"))
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
 pub struct Grid {
     rows: Vec<Vec<Cell>>,
 }
@@ -25,7 +66,70 @@ impl Grid {
     }
 }
 
-pub struct Puzzle;
+/// one line of `.` and `#` for every row.
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in &self.rows {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// how far a traversal moves right and down on every step.
+type Slope = (usize, usize);
+
+fn count_trees(grid: &Grid, (right, down): Slope) -> usize {
+    let mut row = down;
+    let mut col = right;
+    let mut trees = 0;
+    while let Some(cell) = grid.get(row, col) {
+        if matches!(cell, Cell::Tree) {
+            trees += 1;
+        }
+
+        row += down;
+        col += right;
+    }
+
+    trees
+}
+
+fn parse_slope(name: &str, value: &str) -> Result<Slope> {
+    let expected = "`right,down` with down at least 1";
+    let (right, down) = value
+        .split_once(',')
+        .ok_or_else(|| invalid_value(name, value, expected))?;
+    let right = parse_value(name, right.trim(), expected)?;
+    let down = parse_value(name, down.trim(), expected)?;
+    if down == 0 {
+        return Err(invalid_value(name, value, expected));
+    }
+    Ok((right, down))
+}
+
+fn format_slope((right, down): &Slope) -> String {
+    format!("{},{}", right, down)
+}
+
+pub struct Puzzle {
+    /// slope used in part one
+    slope: Slope,
+    /// slopes whose tree counts are multiplied in part two
+    slopes: Vec<Slope>,
+}
+
+impl Default for Puzzle {
+    fn default() -> Self {
+        Self {
+            slope: (3, 1),
+            slopes: vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)],
+        }
+    }
+}
 
 impl crate::PuzzleRunner for Puzzle {
     const DAY: usize = 3;
@@ -33,79 +137,144 @@ impl crate::PuzzleRunner for Puzzle {
     type Second = usize;
     type Input = Grid;
 
-    fn parse_input(&self, filename: &str) -> Result<Self::Input> {
-        let rows = read_lines(filename)?
-            .into_iter()
-            .map(|l| {
-                let l: Vec<Cell> = l
-                    .split("")
-                    .filter_map(|c| match c {
-                        "." => Some(Cell::OpenSquare),
-                        "#" => Some(Cell::Tree),
-                        _ => None,
-                    })
-                    .collect();
-                l
-            })
-            .collect();
+    fn parse_str(&self, input: &str) -> Result<Self::Input> {
+        let rows = parse_lines(Self::DAY, input, parse_row)?;
         Ok(Grid { rows })
     }
 
-    fn part_one(&self, grid: &Self::Input) -> Self::First {
-        let mut row = 1;
-        let mut col = 3;
-        let mut trees = 0;
-        while let Some(cell) = grid.get(row, col) {
-            if matches!(cell, Cell::Tree) {
-                trees += 1;
-            }
+    fn part_one(&self, grid: &Self::Input) -> Result<Self::First> {
+        Ok(count_trees(grid, self.slope))
+    }
 
-            row += 1;
-            col += 3;
-        }
+    fn part_two(&self, grid: &Self::Input, _first: Option<&Self::First>) -> Result<Self::Second> {
+        Ok(self
+            .slopes
+            .iter()
+            .map(|slope| count_trees(grid, *slope))
+            .product())
+    }
 
-        trees
+    fn write_input(&self, grid: &Self::Input) -> Result<String> {
+        Ok(grid.to_string())
     }
 
-    fn part_two(&self, grid: &Self::Input) -> Self::Second {
-        let traversals = vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
-        let mut results = vec![];
-        for traversal in traversals.into_iter() {
-            let mut row = traversal.1;
-            let mut col = traversal.0;
-            let mut trees = 0;
-            while let Some(cell) = grid.get(row, col) {
-                if matches!(cell, Cell::Tree) {
-                    trees += 1;
-                }
+    /// a grid of `size` rows, with the trees on each slope counted as they are placed.
+    fn generate(&self, rng: &mut Rng, size: usize) -> Result<Generated> {
+        let width = rng.between(11, 40) as usize;
+        let rows: Vec<Vec<bool>> = (0..size)
+            .map(|_| (0..width).map(|_| rng.chance(20)).collect())
+            .collect();
+        let trees = |(right, down): Slope| {
+            (1..)
+                .map(|step| (step * down, step * right % width))
+                .take_while(|(row, _)| *row < rows.len())
+                .filter(|(row, col)| rows[*row][*col])
+                .count()
+        };
 
-                row += traversal.1;
-                col += traversal.0;
-            }
+        let second = self
+            .slopes
+            .iter()
+            .try_fold(1usize, |product, slope| product.checked_mul(trees(*slope)))
+            .ok_or_else(|| format!("the part two answer for {} rows overflows", size))?;
+        let input: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|tree| if *tree { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        Ok(Generated::new(
+            input.join("\n") + "\n",
+            trees(self.slope),
+            second,
+        ))
+    }
 
-            results.push(trees);
-        }
+    fn params(&self) -> Vec<Param> {
+        let slopes: Vec<String> = self.slopes.iter().map(format_slope).collect();
+        vec![
+            Param::new(
+                "slope",
+                "`right,down` slope for part one",
+                format_slope(&self.slope),
+            ),
+            Param::new(
+                "slopes",
+                "space separated `right,down` slopes for part two",
+                slopes.join(" "),
+            ),
+        ]
+    }
 
-        results.into_iter().fold(1, |acc, i| acc * i)
+    fn set_param(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "slope" => self.slope = parse_slope(name, value)?,
+            "slopes" => {
+                self.slopes = value
+                    .split_whitespace()
+                    .map(|slope| parse_slope(name, slope))
+                    .collect::<Result<_>>()?;
+                if self.slopes.is_empty() {
+                    return Err(invalid_value(name, value, "at least one slope"));
+                }
+            }
+            _ => return Err(unknown_param(Self::DAY, name)),
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::PuzzleRunner;
+    use crate::{gen::assert_round_trip, PuzzleRunner};
+
+    #[test]
+    fn test_parse_row_errors() {
+        assert_eq!(
+            "3: unexpected `x`, expected `.` or `#`",
+            parse_row("..x#").unwrap_err().to_string()
+        );
+        assert!(parse_row("").is_err());
+        assert!(parse_row(".#.").is_ok());
+    }
 
     #[test]
     fn test_part_one() {
-        let puzzle = Puzzle;
+        let puzzle = Puzzle::default();
         let grid = puzzle.test_input().unwrap();
-        assert_eq!(7, puzzle.part_one(&grid));
+        assert_eq!(7, puzzle.part_one(&grid).unwrap());
     }
 
     #[test]
     fn test_part_two() {
-        let puzzle = Puzzle;
+        let puzzle = Puzzle::default();
         let grid = puzzle.test_input().unwrap();
-        assert_eq!(336, puzzle.part_two(&grid));
+        assert_eq!(336, puzzle.part_two(&grid, None).unwrap());
+    }
+
+    #[test]
+    fn test_slope_params() {
+        let mut puzzle = Puzzle::default();
+        let grid = puzzle.test_input().unwrap();
+        puzzle.set_param("slope", "1,2").unwrap();
+        assert_eq!(2, puzzle.part_one(&grid).unwrap());
+        puzzle.set_param("slopes", "1,1 7,1").unwrap();
+        assert_eq!(2 * 4, puzzle.part_two(&grid, None).unwrap());
+        assert_eq!("1,1 7,1", puzzle.params()[1].value);
+
+        assert!(puzzle.set_param("slope", "1,0").is_err());
+        assert!(puzzle.set_param("slope", "1").is_err());
+        assert!(puzzle.set_param("slopes", "").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let puzzle = Puzzle::default();
+        let grid = puzzle.parse_str("..##\n#...\n").unwrap();
+        assert_eq!("..##\n#...\n", grid.to_string());
+        assert_round_trip(&puzzle);
     }
 }
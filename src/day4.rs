@@ -1,4 +1,11 @@
-use crate::{io::read_lines, PuzzleRunner};
+use std::{fmt, str::FromStr};
+
+use crate::{
+    gen::{Generated, Rng},
+    io::write_records,
+    result::ParseError,
+    PuzzleRunner,
+};
 use lazy_static::lazy_static;
 use regex::Regex;
 
@@ -8,43 +15,38 @@ lazy_static! {
     static ref PASSPORT_ID_RE: Regex = Regex::new(r#"^\d{9}$"#).unwrap();
 }
 
-enum Height {
+pub enum Height {
     Centimeters(u16),
     Inches(u16),
 }
 
 impl Height {
-    fn parse(input: &str) -> Option<Height> {
-        let captures = HEIGHT_RE.captures(input);
-        if captures.is_none() {
-            return None;
-        }
-
-        let captures = captures.unwrap();
+    pub fn parse(input: &str) -> Option<Height> {
+        let captures = HEIGHT_RE.captures(input)?;
         let cap1 = captures.get(1).map(|m| m.as_str());
         let cap2 = captures.get(2).map(|m| m.as_str());
         match (cap1, cap2) {
-            (Some(val), Some("cm")) => val.parse().ok().map(|v| Height::Centimeters(v)),
-            (Some(val), Some("in")) => val.parse().ok().map(|v| Height::Inches(v)),
+            (Some(val), Some("cm")) => val.parse().ok().map(Height::Centimeters),
+            (Some(val), Some("in")) => val.parse().ok().map(Height::Inches),
             _ => None,
         }
     }
 
-    fn valid(input: &str) -> bool {
+    pub fn valid(input: &str) -> bool {
         let hgt = Height::parse(input);
         if hgt.is_none() {
             return false;
         }
         let hgt = hgt.unwrap();
         match hgt {
-            Height::Centimeters(val) if val >= 150 && val <= 193 => true,
-            Height::Inches(val) if val >= 59 && val <= 76 => true,
+            Height::Centimeters(val) if (150..=193).contains(&val) => true,
+            Height::Inches(val) if (59..=76).contains(&val) => true,
             _ => false,
         }
     }
 }
 
-enum EyeColor {
+pub enum EyeColor {
     Amb,
     Blu,
     Brn,
@@ -55,7 +57,7 @@ enum EyeColor {
 }
 
 impl EyeColor {
-    fn parse(input: &str) -> Option<Self> {
+    pub fn parse(input: &str) -> Option<Self> {
         match input {
             "amb" => Some(EyeColor::Amb),
             "blu" => Some(EyeColor::Blu),
@@ -68,17 +70,17 @@ impl EyeColor {
         }
     }
 
-    fn valid(input: &str) -> bool {
+    pub fn valid(input: &str) -> bool {
         let ec = EyeColor::parse(input);
         if ec.is_none() {
             return false;
         }
 
-        return true;
+        true
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
 pub struct Passport {
     byr: Option<String>,
     iyr: Option<String>,
@@ -90,60 +92,109 @@ pub struct Passport {
     cid: Option<String>,
 }
 
-impl Passport {
-    fn parse(input: String) -> Passport {
+impl FromStr for Passport {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> std::result::Result<Self, Self::Err> {
         let mut passport = Passport::default();
-        let parts = input.split_whitespace();
-        for part in parts {
-            let part: Vec<&str> = part.split(":").collect();
-            let key = part.get(0).unwrap();
-            let val = part.get(1).unwrap();
-            match *key {
-                "byr" => passport.byr = Some(val.to_string()),
-                "iyr" => passport.iyr = Some(val.to_string()),
-                "eyr" => passport.eyr = Some(val.to_string()),
-                "hgt" => passport.hgt = Some(val.to_string()),
-                "hcl" => passport.hcl = Some(val.to_string()),
-                "ecl" => passport.ecl = Some(val.to_string()),
-                "pid" => passport.pid = Some(val.to_string()),
-                "cid" => passport.cid = Some(val.to_string()),
-                _ => {}
+        passport.parse_fields(input)?;
+        Ok(passport)
+    }
+}
+
+/// the fields that are present on a single line, in a fixed order.
+impl fmt::Display for Passport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fields = [
+            ("byr", &self.byr),
+            ("iyr", &self.iyr),
+            ("eyr", &self.eyr),
+            ("hgt", &self.hgt),
+            ("hcl", &self.hcl),
+            ("ecl", &self.ecl),
+            ("pid", &self.pid),
+            ("cid", &self.cid),
+        ];
+        let fields: Vec<String> = fields
+            .iter()
+            .filter_map(|(key, val)| val.as_ref().map(|val| format!("{}:{}", key, val)))
+            .collect();
+        write!(f, "{}", fields.join(" "))
+    }
+}
+
+impl Passport {
+    /// adds the space separated `key:value` fields in `line` to the passport.
+    fn parse_fields(&mut self, line: &str) -> std::result::Result<(), ParseError> {
+        for field in line.split_whitespace() {
+            let (key, val) = field
+                .split_once(':')
+                .ok_or_else(|| ParseError::within(line, field, "expected `key:value`"))?;
+            let val = Some(val.to_string());
+            match key {
+                "byr" => self.byr = val,
+                "iyr" => self.iyr = val,
+                "eyr" => self.eyr = val,
+                "hgt" => self.hgt = val,
+                "hcl" => self.hcl = val,
+                "ecl" => self.ecl = val,
+                "pid" => self.pid = val,
+                "cid" => self.cid = val,
+                _ => {
+                    return Err(
+                        ParseError::within(line, key, format!("unknown field `{}`", key))
+                            .with_hint("expected byr/iyr/eyr/hgt/hcl/ecl/pid/cid"),
+                    )
+                }
             }
         }
-        passport
+        Ok(())
+    }
+
+    /// whether every field except `cid` is present, regardless of its value.
+    pub fn has_required_fields(&self) -> bool {
+        self.byr.is_some()
+            && self.iyr.is_some()
+            && self.eyr.is_some()
+            && self.hgt.is_some()
+            && self.hcl.is_some()
+            && self.ecl.is_some()
+            && self.pid.is_some()
     }
 
-    fn valid_byr(&self) -> bool {
+    /// whether every field except `cid` is present and holds a valid value.
+    pub fn is_valid(&self) -> bool {
+        self.valid_byr()
+            && self.valid_ecl()
+            && self.valid_eyr()
+            && self.valid_hcl()
+            && self.valid_hgt()
+            && self.valid_iyr()
+            && self.valid_pid()
+    }
+
+    pub fn valid_byr(&self) -> bool {
         if self.byr.is_none() {
             return false;
         }
-        match self.byr.as_ref().unwrap().parse::<u16>().ok() {
-            Some(byr) if byr >= 1920 && byr <= 2002 => true,
-            _ => false,
-        }
+        matches!(self.byr.as_ref().unwrap().parse::<u16>().ok(), Some(byr) if (1920..=2002).contains(&byr))
     }
 
-    fn valid_iyr(&self) -> bool {
+    pub fn valid_iyr(&self) -> bool {
         if self.iyr.is_none() {
             return false;
         }
-        match self.iyr.as_ref().unwrap().parse::<u16>().ok() {
-            Some(iyr) if iyr >= 2010 && iyr <= 2020 => true,
-            _ => false,
-        }
+        matches!(self.iyr.as_ref().unwrap().parse::<u16>().ok(), Some(iyr) if (2010..=2020).contains(&iyr))
     }
 
-    fn valid_eyr(&self) -> bool {
+    pub fn valid_eyr(&self) -> bool {
         if self.eyr.is_none() {
             return false;
         }
-        match self.eyr.as_ref().unwrap().parse::<u16>().ok() {
-            Some(eyr) if eyr >= 2020 && eyr <= 2030 => true,
-            _ => false,
-        }
+        matches!(self.eyr.as_ref().unwrap().parse::<u16>().ok(), Some(eyr) if (2020..=2030).contains(&eyr))
     }
 
-    fn valid_ecl(&self) -> bool {
+    pub fn valid_ecl(&self) -> bool {
         if self.ecl.is_none() {
             return false;
         }
@@ -151,7 +202,7 @@ impl Passport {
         EyeColor::valid(self.ecl.as_ref().unwrap())
     }
 
-    fn valid_hgt(&self) -> bool {
+    pub fn valid_hgt(&self) -> bool {
         if self.hgt.is_none() {
             return false;
         }
@@ -159,18 +210,12 @@ impl Passport {
         Height::valid(self.hgt.as_ref().unwrap())
     }
 
-    fn valid_hcl(&self) -> bool {
-        match &self.hcl {
-            Some(hcl) if HAIR_COLOR_RE.is_match(&hcl) => true,
-            _ => false,
-        }
+    pub fn valid_hcl(&self) -> bool {
+        matches!(&self.hcl, Some(hcl) if HAIR_COLOR_RE.is_match(hcl))
     }
 
-    fn valid_pid(&self) -> bool {
-        match &self.pid {
-            Some(pid) if PASSPORT_ID_RE.is_match(pid) => true,
-            _ => false,
-        }
+    pub fn valid_pid(&self) -> bool {
+        matches!(&self.pid, Some(pid) if PASSPORT_ID_RE.is_match(pid))
     }
 }
 
@@ -182,73 +227,160 @@ impl PuzzleRunner for Puzzle {
     type Second = usize;
     type Input = Vec<Passport>;
 
-    fn parse_input(&self, filename: &str) -> crate::result::Result<Self::Input> {
-        let mut data = vec![];
-
-        let lines = read_lines(&filename)?.into_iter();
-        let mut parts = vec![];
-        for line in lines {
+    fn parse_str(&self, input: &str) -> crate::result::Result<Self::Input> {
+        let mut passports = vec![];
+        let mut passport = Passport::default();
+        for (idx, line) in input.lines().enumerate() {
             if line.is_empty() {
-                data.push(parts.join(" "));
-                parts = vec![];
+                passports.push(passport);
+                passport = Passport::default();
                 continue;
             }
 
-            parts.push(line);
+            passport
+                .parse_fields(line)
+                .map_err(|e| e.at(Self::DAY, idx + 1, line))?;
         }
 
-        data.push(parts.join(" "));
-        Ok(data.into_iter().map(Passport::parse).collect())
+        passports.push(passport);
+        Ok(passports)
+    }
+
+    fn part_one(&self, entries: &Self::Input) -> crate::result::Result<Self::First> {
+        Ok(entries.iter().filter(|p| p.has_required_fields()).count())
     }
 
-    fn part_one(&self, entries: &Self::Input) -> Self::First {
-        entries
-            .into_iter()
-            .filter(|p| {
-                p.byr.is_some()
-                    && p.iyr.is_some()
-                    && p.eyr.is_some()
-                    && p.hgt.is_some()
-                    && p.hcl.is_some()
-                    && p.ecl.is_some()
-                    && p.pid.is_some()
-            })
-            .count()
+    fn part_two(
+        &self,
+        entries: &Self::Input,
+        _first: Option<&Self::First>,
+    ) -> crate::result::Result<Self::Second> {
+        Ok(entries.iter().filter(|p| p.is_valid()).count())
     }
 
-    fn part_two(&self, entries: &Self::Input) -> Self::Second {
-        entries
-            .into_iter()
-            .filter(|p| {
-                p.valid_byr()
-                    && p.valid_ecl()
-                    && p.valid_eyr()
-                    && p.valid_hcl()
-                    && p.valid_hgt()
-                    && p.valid_iyr()
-                    && p.valid_pid()
-            })
-            .count()
+    fn write_input(&self, entries: &Self::Input) -> crate::result::Result<String> {
+        Ok(write_records(entries))
+    }
+
+    /// batches of passports that are each either missing a field, complete with one invalid
+    /// value, or valid.
+    fn generate(&self, rng: &mut Rng, size: usize) -> crate::result::Result<Generated> {
+        let required = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+        let (mut complete, mut valid) = (0, 0);
+        let mut passports = vec![];
+        for _ in 0..size {
+            let mut fields: Vec<(&str, String)> = required
+                .iter()
+                .map(|key| (*key, field_value(rng, key, true)))
+                .collect();
+            if rng.chance(50) {
+                fields.push(("cid", field_value(rng, "cid", true)));
+            }
+
+            match rng.index(3) {
+                0 => {
+                    fields.remove(rng.index(required.len()));
+                }
+                1 => {
+                    let idx = rng.index(required.len());
+                    fields[idx].1 = field_value(rng, fields[idx].0, false);
+                    complete += 1;
+                }
+                _ => {
+                    complete += 1;
+                    valid += 1;
+                }
+            }
+
+            rng.shuffle(&mut fields);
+            let mut passport = String::new();
+            for (idx, (key, value)) in fields.iter().enumerate() {
+                if idx > 0 {
+                    passport.push(if rng.chance(25) { '\n' } else { ' ' });
+                }
+                passport += &format!("{}:{}", key, value);
+            }
+            passports.push(passport);
+        }
+        Ok(Generated::new(
+            passports.join("\n\n") + "\n",
+            complete,
+            valid,
+        ))
+    }
+}
+
+/// a random value for the field `key`, which passes its validation when `valid` is set and
+/// fails it otherwise.
+fn field_value(rng: &mut Rng, key: &str, valid: bool) -> String {
+    let year = |rng: &mut Rng, low: i64, high: i64| match valid {
+        true => rng.between(low, high),
+        false if rng.chance(50) => rng.between(low - 20, low - 1),
+        false => rng.between(high + 1, high + 20),
+    };
+    match key {
+        "byr" => year(rng, 1920, 2002).to_string(),
+        "iyr" => year(rng, 2010, 2020).to_string(),
+        "eyr" => year(rng, 2020, 2030).to_string(),
+        "hgt" => match (valid, rng.chance(50)) {
+            (true, true) => format!("{}cm", rng.between(150, 193)),
+            (true, false) => format!("{}in", rng.between(59, 76)),
+            (false, true) => format!("{}cm", rng.between(194, 250)),
+            (false, false) => rng.between(59, 76).to_string(),
+        },
+        "hcl" => {
+            let hex: String = (0..6)
+                .map(|_| *rng.choose(b"0123456789abcdef") as char)
+                .collect();
+            match valid {
+                true => format!("#{}", hex),
+                false => hex,
+            }
+        }
+        "ecl" => match valid {
+            true => rng.choose(&["amb", "blu", "brn", "gry", "grn", "hzl", "oth"]),
+            false => rng.choose(&["red", "pnk", "xry", "zzz"]),
+        }
+        .to_string(),
+        "pid" => {
+            let digits = if valid { 9 } else { *rng.choose(&[8, 10]) };
+            (0..digits)
+                .map(|_| (b'0' + rng.index(10) as u8) as char)
+                .collect()
+        }
+        _ => rng.between(100, 350).to_string(),
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{EyeColor, Height, Puzzle, HAIR_COLOR_RE, PASSPORT_ID_RE};
-    use crate::PuzzleRunner;
+    use super::{EyeColor, Height, Passport, Puzzle, HAIR_COLOR_RE, PASSPORT_ID_RE};
+    use crate::{gen::assert_round_trip, PuzzleRunner};
+
+    #[test]
+    fn test_parse_passport_errors() {
+        assert_eq!(
+            "10: expected `key:value`",
+            "byr:1937 iyr".parse::<Passport>().unwrap_err().to_string()
+        );
+        assert_eq!(
+            "1: unknown field `abc`, expected byr/iyr/eyr/hgt/hcl/ecl/pid/cid",
+            "abc:1".parse::<Passport>().unwrap_err().to_string()
+        );
+    }
 
     #[test]
     fn test_part_one() {
         let puzzle = Puzzle;
         let entries = puzzle.test_input().unwrap();
-        assert_eq!(2, puzzle.part_one(&entries));
+        assert_eq!(2, puzzle.part_one(&entries).unwrap());
     }
 
     #[test]
     fn test_part_two() {
         let puzzle = Puzzle;
         let entries = puzzle.parse_input("./data/day4_part_two_test.txt").unwrap();
-        assert_eq!(4, puzzle.part_two(&entries));
+        assert_eq!(4, puzzle.part_two(&entries, None).unwrap());
     }
 
     #[test]
@@ -277,4 +409,17 @@ mod test {
         assert!(PASSPORT_ID_RE.is_match("123456789"));
         assert!(!PASSPORT_ID_RE.is_match("0123456789"));
     }
+
+    #[test]
+    fn test_round_trip() {
+        let passport: Passport = "hcl:#cfa07d cid:88 byr:1937\tiyr:2017".parse().unwrap();
+        assert_eq!("byr:1937 iyr:2017 hcl:#cfa07d cid:88", passport.to_string());
+        let puzzle = Puzzle;
+        let passports = puzzle.parse_str("\nbyr:1937\n\n\niyr:2017\n").unwrap();
+        assert_eq!(
+            "\nbyr:1937\n\n\niyr:2017\n",
+            puzzle.write_input(&passports).unwrap()
+        );
+        assert_round_trip(&puzzle);
+    }
 }
@@ -1,4 +1,11 @@
-use crate::{io::read_lines, PuzzleRunner};
+use std::{convert::TryFrom, fmt};
+
+use crate::{
+    gen::{Generated, Rng},
+    io::{parse_lines, write_lines},
+    result::{Error, ParseError},
+    PuzzleRunner,
+};
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Seat {
@@ -7,28 +14,53 @@ pub struct Seat {
 }
 
 impl Seat {
-    fn id(&self) -> u16 {
+    pub fn id(&self) -> u16 {
         self.row * 8 + self.col
     }
 }
 
-impl From<&str> for Seat {
-    fn from(s: &str) -> Self {
-        // all entries have 7 for row and 3 for column
-        assert!(s.len() == 10);
-
-        // first 7 characters are the row number, last 3 are the column.
-        let (row, col) = s.split_at(7);
+impl TryFrom<&str> for Seat {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        // first 7 characters are the row number, last 3 are the column. Both are binary numbers,
+        // row uses B for 1 and F for 0 and column uses R for 1 and L for 0.
+        let mut row = 0;
+        let mut col = 0;
+        for (idx, c) in s.chars().enumerate() {
+            match (idx, c) {
+                (0..=6, 'F') | (0..=6, 'B') => row = row << 1 | (c == 'B') as u16,
+                (0..=6, _) => {
+                    return Err(ParseError::new(idx + 1, format!("unexpected `{}`", c))
+                        .with_hint("expected F or B"))
+                }
+                (7..=9, 'L') | (7..=9, 'R') => col = col << 1 | (c == 'R') as u16,
+                (7..=9, _) => {
+                    return Err(ParseError::new(idx + 1, format!("unexpected `{}`", c))
+                        .with_hint("expected L or R"))
+                }
+                _ => return Err(ParseError::new(idx + 1, "expected exactly 10 characters")),
+            }
+        }
 
-        // row and column are binary numbers, row uses B for 1 and F for 0 and column uses R
-        // for 1 and L for 0.
-        let row = row.replace("B", "1").replace("F", "0");
-        let col = col.replace("R", "1").replace("L", "0");
+        if s.chars().count() < 10 {
+            return Err(ParseError::end_of(s, "expected exactly 10 characters"));
+        }
 
-        let row = u16::from_str_radix(&row, 2).unwrap();
-        let col = u16::from_str_radix(&col, 2).unwrap();
+        Ok(Self { row, col })
+    }
+}
 
-        Self { row, col }
+/// the boarding pass, with the row as F and B and the column as L and R.
+impl fmt::Display for Seat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for bit in (0..7).rev() {
+            write!(f, "{}", if self.row >> bit & 1 == 1 { 'B' } else { 'F' })?;
+        }
+        for bit in (0..3).rev() {
+            write!(f, "{}", if self.col >> bit & 1 == 1 { 'R' } else { 'L' })?;
+        }
+        Ok(())
     }
 }
 
@@ -40,41 +72,112 @@ impl PuzzleRunner for Puzzle {
     type Second = u16;
     type Input = Vec<Seat>;
 
-    fn parse_input(&self, filename: &str) -> crate::result::Result<Self::Input> {
-        Ok(read_lines(filename)?
-            .into_iter()
-            .map(|c| Seat::from(c.as_str()))
-            .collect())
+    fn parse_str(&self, input: &str) -> crate::result::Result<Self::Input> {
+        parse_lines(Self::DAY, input, |s| Seat::try_from(s))
     }
 
-    fn part_one(&self, entries: &Self::Input) -> Self::First {
-        entries.into_iter().map(Seat::id).max().unwrap_or_default()
+    fn part_one(&self, entries: &Self::Input) -> crate::result::Result<Self::First> {
+        entries
+            .iter()
+            .map(Seat::id)
+            .max()
+            .ok_or_else(|| Error::NoSolution("there are no seats to pick from".into()))
     }
 
-    fn part_two(&self, entries: &Self::Input) -> Self::Second {
+    fn part_two(
+        &self,
+        entries: &Self::Input,
+        _first: Option<&Self::First>,
+    ) -> crate::result::Result<Self::Second> {
         let ids: Vec<u16> = entries.iter().map(|c| c.id()).collect();
-        let max_id = entries.into_iter().map(Seat::id).max().unwrap_or_default();
-        let min_id = entries.into_iter().map(Seat::id).min().unwrap_or_default();
+        let max_id = entries.iter().map(Seat::id).max().unwrap_or_default();
+        let min_id = entries.iter().map(Seat::id).min().unwrap_or_default();
         for id in min_id..max_id {
             if ids.iter().find(|&i| i == &id).is_none() {
-                return id;
+                return Ok(id);
             }
         }
-        0
+        Err(Error::NoSolution(
+            "there is no free seat between the occupied ones".into(),
+        ))
+    }
+
+    fn write_input(&self, entries: &Self::Input) -> crate::result::Result<String> {
+        Ok(write_lines(entries))
+    }
+
+    /// `size` boarding passes for a run of consecutive seats, apart from a single free one.
+    fn generate(&self, rng: &mut Rng, size: usize) -> crate::result::Result<Generated> {
+        // ids go up to 127 * 8 + 7, and the free seat needs an occupied one on either side
+        if !(2..=1022).contains(&size) {
+            return Err("day 5 inputs hold between 2 and 1022 seats".into());
+        }
+        let first = rng.between(0, 1022 - size as i64) as u16;
+        let last = first + size as u16;
+        let free = rng.between(first as i64 + 1, last as i64 - 1) as u16;
+
+        let mut passes: Vec<String> = (first..=last)
+            .filter(|id| *id != free)
+            .map(|id| {
+                let (row, col) = (id / 8, id % 8);
+                let bits = |value: u16, len: usize, one: char, zero: char| -> String {
+                    (0..len)
+                        .rev()
+                        .map(|bit| if value >> bit & 1 == 1 { one } else { zero })
+                        .collect()
+                };
+                bits(row, 7, 'B', 'F') + &bits(col, 3, 'R', 'L')
+            })
+            .collect();
+        rng.shuffle(&mut passes);
+        Ok(Generated::new(passes.join("\n") + "\n", last, free))
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::{Puzzle, Seat};
-    use crate::PuzzleRunner;
+    use crate::{gen::assert_round_trip, PuzzleRunner};
+    use std::convert::TryFrom;
 
     #[test]
     fn test_parse_seat() {
-        assert_eq!(Seat { row: 44, col: 5 }, Seat::from("FBFBBFFRLR"));
-        assert_eq!(Seat { row: 70, col: 7 }, Seat::from("BFFFBBFRRR"));
-        assert_eq!(Seat { row: 14, col: 7 }, Seat::from("FFFBBBFRRR"));
-        assert_eq!(Seat { row: 102, col: 4 }, Seat::from("BBFFBBFRLL"));
+        assert_eq!(
+            Seat { row: 44, col: 5 },
+            Seat::try_from("FBFBBFFRLR").unwrap()
+        );
+        assert_eq!(
+            Seat { row: 70, col: 7 },
+            Seat::try_from("BFFFBBFRRR").unwrap()
+        );
+        assert_eq!(
+            Seat { row: 14, col: 7 },
+            Seat::try_from("FFFBBBFRRR").unwrap()
+        );
+        assert_eq!(
+            Seat { row: 102, col: 4 },
+            Seat::try_from("BBFFBBFRLL").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_seat_errors() {
+        assert_eq!(
+            "3: unexpected `L`, expected F or B",
+            Seat::try_from("FBLBBFFRLR").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "10: unexpected `B`, expected L or R",
+            Seat::try_from("FBFBBFFRLB").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "11: expected exactly 10 characters",
+            Seat::try_from("FBFBBFFRLRR").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "4: expected exactly 10 characters",
+            Seat::try_from("FBF").unwrap_err().to_string()
+        );
     }
 
     #[test]
@@ -95,6 +198,21 @@ mod test {
         ];
 
         let puzzle = Puzzle;
-        assert_eq!(820, puzzle.part_one(&seats));
+        assert_eq!(820, puzzle.part_one(&seats).unwrap());
+    }
+
+    #[test]
+    fn test_part_two_no_free_seat() {
+        let seats = vec![Seat { row: 1, col: 0 }, Seat { row: 1, col: 1 }];
+
+        let puzzle = Puzzle;
+        assert!(puzzle.part_two(&seats, None).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!("FBFBBFFRLR", Seat { row: 44, col: 5 }.to_string());
+        assert_eq!("BBBBBBBRRR", Seat { row: 127, col: 7 }.to_string());
+        assert_round_trip(&Puzzle);
     }
 }
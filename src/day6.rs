@@ -1,12 +1,41 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, fmt};
 
-use crate::{io::read_lines, PuzzleRunner};
+use crate::{
+    gen::{Generated, Rng},
+    io::write_records,
+    result::ParseError,
+    PuzzleRunner,
+};
 
-#[derive(Default)]
+#[derive(Debug, Default, PartialEq)]
 pub struct Group {
     answers: Vec<Vec<char>>,
 }
 
+/// the answers of every person on a line of their own.
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let people: Vec<String> = self
+            .answers
+            .iter()
+            .map(|answers| answers.iter().collect())
+            .collect();
+        write!(f, "{}", people.join("\n"))
+    }
+}
+
+/// answers of a single person, one lowercase letter per question answered with yes.
+fn parse_answers(line: &str) -> std::result::Result<Vec<char>, ParseError> {
+    line.chars()
+        .enumerate()
+        .map(|(idx, c)| match c {
+            'a'..='z' => Ok(c),
+            _ => Err(ParseError::new(idx + 1, format!("unexpected `{}`", c))
+                .with_hint("expected a question from a to z")),
+        })
+        .collect()
+}
+
 pub struct Puzzle;
 
 impl PuzzleRunner for Puzzle {
@@ -15,70 +44,137 @@ impl PuzzleRunner for Puzzle {
     type Second = usize;
     type Input = Vec<Group>;
 
-    fn parse_input(&self, filename: &str) -> crate::result::Result<Self::Input> {
-        let lines = read_lines(filename)?;
+    fn parse_str(&self, input: &str) -> crate::result::Result<Self::Input> {
+        let lines = input.lines();
         let mut groups = vec![];
         let mut group = Group::default();
-        for line in lines {
+        for (idx, line) in lines.enumerate() {
             if line.is_empty() {
                 groups.push(group);
                 group = Group::default();
                 continue;
             }
 
-            group.answers.push(line.chars().collect());
+            let answers = parse_answers(line).map_err(|e| e.at(Self::DAY, idx + 1, line))?;
+            group.answers.push(answers);
         }
         groups.push(group);
         Ok(groups)
     }
 
-    fn part_one(&self, entries: &Self::Input) -> Self::First {
+    fn part_one(&self, entries: &Self::Input) -> crate::result::Result<Self::First> {
         let out: Vec<HashSet<&char>> = entries
-            .into_iter()
+            .iter()
             .map(|g| g.answers.iter().flatten().collect())
             .collect();
-        out.into_iter().fold(0, |acc, n| acc + n.len())
+        Ok(out.into_iter().fold(0, |acc, n| acc + n.len()))
     }
 
-    fn part_two(&self, entries: &Self::Input) -> Self::Second {
+    fn part_two(
+        &self,
+        entries: &Self::Input,
+        _first: Option<&Self::First>,
+    ) -> crate::result::Result<Self::Second> {
         let out: Vec<HashSet<&char>> = entries
-            .into_iter()
+            .iter()
             .map(|g| {
                 g.answers
                     .iter()
                     .enumerate()
                     .fold(HashSet::new(), |acc, (idx, n)| {
                         if idx == 0 {
-                            return n.into_iter().collect();
+                            return n.iter().collect();
                         }
 
-                        HashSet::from(n.into_iter().collect())
+                        n.iter()
+                            .collect::<HashSet<_>>()
                             .intersection(&acc)
                             .cloned()
                             .collect()
                     })
             })
             .collect();
-        out.into_iter().fold(0, |acc, n| acc + n.len())
+        Ok(out.into_iter().fold(0, |acc, n| acc + n.len()))
+    }
+
+    fn write_input(&self, entries: &Self::Input) -> crate::result::Result<String> {
+        Ok(write_records(entries))
+    }
+
+    /// `size` groups whose people share a planted set of answers. One person in each group
+    /// answers only the shared questions, so no other question is answered by everyone.
+    fn generate(&self, rng: &mut Rng, size: usize) -> crate::result::Result<Generated> {
+        let (mut anyone, mut everyone) = (0, 0);
+        let mut groups = vec![];
+        for _ in 0..size {
+            let mut letters: Vec<char> = ('a'..='z').collect();
+            rng.shuffle(&mut letters);
+            let shared = &letters[..rng.between(1, 6) as usize];
+            let extra = &letters[shared.len()..];
+
+            let mut people: Vec<Vec<char>> = vec![shared.to_vec()];
+            let mut answered: HashSet<char> = shared.iter().copied().collect();
+            for _ in 0..rng.index(5) {
+                let mut person = shared.to_vec();
+                for _ in 0..rng.index(5) {
+                    let letter = *rng.choose(extra);
+                    if !person.contains(&letter) {
+                        person.push(letter);
+                    }
+                }
+                answered.extend(person.iter());
+                people.push(person);
+            }
+            anyone += answered.len();
+            everyone += shared.len();
+
+            rng.shuffle(&mut people);
+            let people: Vec<String> = people
+                .iter_mut()
+                .map(|person| {
+                    rng.shuffle(person);
+                    person.iter().collect()
+                })
+                .collect();
+            groups.push(people.join("\n"));
+        }
+        Ok(Generated::new(groups.join("\n\n") + "\n", anyone, everyone))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::Puzzle;
-    use crate::PuzzleRunner;
+    use super::{parse_answers, Puzzle};
+    use crate::{gen::assert_round_trip, PuzzleRunner};
+
+    #[test]
+    fn test_parse_answers() {
+        assert_eq!(vec!['a', 'b', 'c'], parse_answers("abc").unwrap());
+        assert_eq!(
+            "2: unexpected `B`, expected a question from a to z",
+            parse_answers("aBc").unwrap_err().to_string()
+        );
+    }
 
     #[test]
     fn test_part_one() {
         let puzzle = Puzzle;
         let entries = puzzle.test_input().unwrap();
-        assert_eq!(11, puzzle.part_one(&entries));
+        assert_eq!(11, puzzle.part_one(&entries).unwrap());
     }
 
     #[test]
     fn test_part_two() {
         let puzzle = Puzzle;
         let entries = puzzle.test_input().unwrap();
-        assert_eq!(6, puzzle.part_two(&entries));
+        assert_eq!(6, puzzle.part_two(&entries, None).unwrap());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let puzzle = Puzzle;
+        let groups = puzzle.parse_str("abc\n\na\nb\n").unwrap();
+        assert_eq!("abc\n\na\nb\n", puzzle.write_input(&groups).unwrap());
+        assert_round_trip(&puzzle);
     }
 }
@@ -1,6 +1,18 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
 
-use crate::{io::read_lines, PuzzleRunner};
+use crate::{
+    gen::{Generated, Rng},
+    io::{parse_lines, write_lines},
+    params::{invalid_value, unknown_param, Param},
+    repl::{unknown_query, Query, QueryState},
+    result::ParseError,
+    strategy::{unknown_strategy, Strategy},
+    PuzzleRunner,
+};
 
 #[derive(Debug, PartialEq)]
 pub struct Rule {
@@ -8,37 +20,139 @@ pub struct Rule {
     bags: Vec<(usize, String)>,
 }
 
-impl From<String> for Rule {
-    fn from(input: String) -> Self {
-        let parts: Vec<&str> = input.split("bags contain").collect();
-        assert!(parts.len() == 2);
+impl FromStr for Rule {
+    type Err = ParseError;
 
-        let color = parts[0].trim().to_string();
-        if parts[1].trim() == "no other bags." {
-            return Rule {
+    fn from_str(input: &str) -> std::result::Result<Self, Self::Err> {
+        let (color, contents) = input
+            .split_once(" bags contain ")
+            .ok_or_else(|| ParseError::new(1, "expected `<color> bags contain`"))?;
+        let color = color.trim().to_string();
+        if color.is_empty() {
+            return Err(ParseError::new(1, "expected a bag color"));
+        }
+
+        let contents = contents
+            .strip_suffix('.')
+            .ok_or_else(|| ParseError::end_of(input, "expected `.` at the end of the rule"))?;
+        if contents == "no other bags" {
+            return Ok(Rule {
                 color,
                 bags: vec![],
-            };
+            });
         }
 
-        let bags = parts[1]
-            .split(",")
-            .into_iter()
-            .map(|i| {
-                let parts: Vec<&str> = i.trim().splitn(2, ' ').collect();
-                assert!(parts.len() == 2);
-                (
-                    parts[0].trim().parse().unwrap(),
-                    parts[1].split(" bag").collect::<Vec<&str>>()[0].to_string(),
-                )
+        let bags = contents
+            .split(',')
+            .map(|bag| {
+                let bag = bag.trim();
+                let (count, rest) = bag
+                    .split_once(' ')
+                    .ok_or_else(|| ParseError::within(input, bag, "expected bag count"))?;
+                let count = count
+                    .parse()
+                    .map_err(|_| ParseError::within(input, count, "expected bag count"))?;
+                let color = rest
+                    .strip_suffix(" bags")
+                    .or_else(|| rest.strip_suffix(" bag"))
+                    .ok_or_else(|| {
+                        ParseError::within(input, rest, "expected `bag` or `bags` after the color")
+                    })?;
+                Ok((count, color.to_string()))
+            })
+            .collect::<std::result::Result<_, _>>()?;
+
+        Ok(Rule { color, bags })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.bags.is_empty() {
+            return write!(f, "{} bags contain no other bags.", self.color);
+        }
+
+        let bags: Vec<String> = self
+            .bags
+            .iter()
+            .map(|(count, color)| match count {
+                1 => format!("1 {} bag", color),
+                _ => format!("{} {} bags", count, color),
             })
             .collect();
+        write!(f, "{} bags contain {}.", self.color, bags.join(", "))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    /// on the path the search is following
+    Open,
+    Done,
+}
 
-        Rule { color, bags }
+/// finds the rule, and the bag in it, that closes a loop of bags holding each other.
+/// the search keeps its own stack, as a long chain of rules would overflow the real one.
+fn find_cycle(rules: &[Rule]) -> Option<(usize, usize)> {
+    let index: HashMap<&str, usize> = rules
+        .iter()
+        .enumerate()
+        .map(|(idx, rule)| (rule.color.as_str(), idx))
+        .collect();
+    let mut visits = vec![Visit::New; rules.len()];
+    for root in 0..rules.len() {
+        if visits[root] != Visit::New {
+            continue;
+        }
+        visits[root] = Visit::Open;
+        let mut stack = vec![(root, 0)];
+        while let Some((rule, bag)) = stack.pop() {
+            let color = match rules[rule].bags.get(bag) {
+                Some((_, color)) => color,
+                None => {
+                    visits[rule] = Visit::Done;
+                    continue;
+                }
+            };
+            stack.push((rule, bag + 1));
+            if let Some(&inner) = index.get(color.as_str()) {
+                match visits[inner] {
+                    Visit::New => {
+                        visits[inner] = Visit::Open;
+                        stack.push((inner, 0));
+                    }
+                    Visit::Open => return Some((rule, bag)),
+                    Visit::Done => {}
+                }
+            }
+        }
     }
+    None
+}
+
+pub struct Puzzle {
+    /// bag the puzzle asks about
+    color: String,
+    solver: Solver,
 }
 
-pub struct Puzzle;
+#[derive(Clone, Copy, PartialEq)]
+enum Solver {
+    /// walks the rules again for every bag
+    Recursive,
+    /// remembers the answer for every color in a graph of the rules
+    Memoized,
+}
+
+impl Default for Puzzle {
+    fn default() -> Self {
+        Self {
+            color: "shiny gold".into(),
+            solver: Solver::Recursive,
+        }
+    }
+}
 
 impl PuzzleRunner for Puzzle {
     const DAY: usize = 7;
@@ -46,14 +160,53 @@ impl PuzzleRunner for Puzzle {
     type Second = usize;
     type Input = Vec<Rule>;
 
-    fn parse_input(&self, filename: &str) -> crate::result::Result<Self::Input> {
-        Ok(read_lines(filename)?.into_iter().map(Rule::from).collect())
+    /// rules for a color that already has one are rejected, as the strategies would each
+    /// pick a different one of them. so are bags that end up inside themselves, which
+    /// would have no count.
+    fn parse_str(&self, input: &str) -> crate::result::Result<Self::Input> {
+        let rules: Vec<Rule> = parse_lines(Self::DAY, input, str::parse)?;
+        let mut first_rules = HashMap::new();
+        for (idx, (rule, line)) in rules.iter().zip(input.lines()).enumerate() {
+            if let Some(first) = first_rules.insert(rule.color.as_str(), idx + 1) {
+                let start = line.len() - line.trim_start().len();
+                let color = &line[start..start + rule.color.len()];
+                return Err(ParseError::within(
+                    line,
+                    color,
+                    format!("`{}` already has a rule", rule.color),
+                )
+                .with_hint(format!("the first one is on line {}", first))
+                .at(Self::DAY, idx + 1, line));
+            }
+        }
+        if let Some((idx, bag)) = find_cycle(&rules) {
+            let line = input.lines().nth(idx).unwrap_or_default();
+            let color = &rules[idx].bags[bag].1;
+            // the rule parsed, so its bags are the comma separated `<count> <color> bag(s)`
+            let inner = line
+                .split_once(" bags contain ")
+                .and_then(|(_, contents)| contents.split(',').nth(bag))
+                .and_then(|bag| bag.trim().split_once(' '))
+                .map_or(line, |(_, rest)| &rest[..color.len()]);
+            return Err(ParseError::within(
+                line,
+                inner,
+                format!("`{}` bags end up inside themselves", color),
+            )
+            .with_hint("bags cannot hold each other in a loop")
+            .at(Self::DAY, idx + 1, line));
+        }
+        Ok(rules)
     }
 
-    fn part_one(&self, entries: &Self::Input) -> Self::First {
+    fn part_one(&self, entries: &Self::Input) -> crate::result::Result<Self::First> {
+        if self.solver == Solver::Memoized {
+            return Ok(Graph::new(entries).holders(&self.color));
+        }
+
         let mut bags_that_can = HashSet::new();
         for entry in entries {
-            if entry.bags.iter().any(|f| f.1 == "shiny gold") {
+            if entry.bags.iter().any(|f| f.1 == self.color) {
                 bags_that_can.insert(entry.color.clone());
             }
         }
@@ -66,27 +219,286 @@ impl PuzzleRunner for Puzzle {
             }
         }
 
-        count
+        Ok(count)
     }
 
-    fn part_two(&self, entries: &Self::Input) -> Self::Second {
+    fn part_two(
+        &self,
+        entries: &Self::Input,
+        _first: Option<&Self::First>,
+    ) -> crate::result::Result<Self::Second> {
+        if self.solver == Solver::Memoized {
+            return Ok(Graph::new(entries).inside(&self.color, &mut HashMap::new()));
+        }
+
         let mut bag_count = 0;
-        add_bags(entries, &mut bag_count, "shiny gold");
-        bag_count
+        add_bags(entries, &mut bag_count, &self.color);
+        Ok(bag_count)
+    }
+
+    fn params(&self) -> Vec<Param> {
+        vec![Param::new(
+            "color",
+            "bag the puzzle asks about",
+            &self.color,
+        )]
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> crate::result::Result<()> {
+        match name {
+            "color" if value.trim().is_empty() => {
+                return Err(invalid_value(name, value, "a bag color"))
+            }
+            "color" => self.color = value.trim().to_string(),
+            _ => return Err(unknown_param(Self::DAY, name)),
+        }
+        Ok(())
+    }
+
+    fn write_input(&self, entries: &Self::Input) -> crate::result::Result<String> {
+        Ok(write_lines(entries))
+    }
+
+    /// `size` rules in a few layers, where bags only hold bags from the next two layers. The
+    /// bag the puzzle asks about sits three layers from the bottom, which keeps the number of
+    /// bags inside it small, and some of the bags above hold it directly.
+    fn generate(&self, rng: &mut Rng, size: usize) -> crate::result::Result<Generated> {
+        let size = size.max(1);
+        let layers = size.min(6);
+        let layer = |idx: usize| idx * layers / size;
+        let target_layer = layers.saturating_sub(3);
+        let targets: Vec<usize> = (0..size)
+            .filter(|idx| layer(*idx) == target_layer)
+            .collect();
+        let target = *rng.choose(&targets);
+
+        let mut names: Vec<String> = (0..)
+            .map(color_name)
+            .filter(|name| *name != self.color)
+            .take(size - 1)
+            .collect();
+        rng.shuffle(&mut names);
+        names.insert(target, self.color.clone());
+
+        let mut bags: Vec<Vec<(usize, usize)>> = vec![vec![]; size];
+        for (idx, contents) in bags.iter_mut().enumerate() {
+            let inner: Vec<usize> = (idx + 1..size)
+                .filter(|inner| (layer(idx) + 1..=layer(idx) + 2).contains(&layer(*inner)))
+                .collect();
+            if inner.is_empty() {
+                continue;
+            }
+            let picks = if idx == target {
+                1 + rng.index(3)
+            } else {
+                rng.index(4)
+            };
+            let mut picked: Vec<usize> = (0..picks).map(|_| *rng.choose(&inner)).collect();
+            if inner.contains(&target) && rng.chance(5) {
+                picked.push(target);
+            }
+            for bag in picked {
+                if contents.iter().all(|(_, other)| *other != bag) {
+                    contents.push((rng.between(1, 5) as usize, bag));
+                }
+            }
+        }
+
+        // bags only hold later bags, so walking backwards sees every inner bag first
+        let mut holds = vec![false; size];
+        let mut inside = vec![0; size];
+        for idx in (0..size).rev() {
+            holds[idx] = bags[idx]
+                .iter()
+                .any(|(_, bag)| *bag == target || holds[*bag]);
+            inside[idx] = bags[idx]
+                .iter()
+                .map(|(count, bag)| count * (1 + inside[*bag]))
+                .sum();
+        }
+
+        let mut rules: Vec<String> = bags
+            .iter()
+            .enumerate()
+            .map(|(idx, contents)| {
+                let contents: Vec<String> = contents
+                    .iter()
+                    .map(|(count, bag)| {
+                        let plural = if *count == 1 { "" } else { "s" };
+                        format!("{} {} bag{}", count, names[*bag], plural)
+                    })
+                    .collect();
+                let contents = match contents.is_empty() {
+                    true => "no other bags".to_string(),
+                    false => contents.join(", "),
+                };
+                format!("{} bags contain {}.", names[idx], contents)
+            })
+            .collect();
+        rng.shuffle(&mut rules);
+        Ok(Generated::new(
+            rules.join("\n") + "\n",
+            holds.iter().filter(|holds| **holds).count(),
+            inside[target],
+        ))
+    }
+
+    fn strategies(&self) -> Vec<Strategy> {
+        vec![
+            Strategy::new("recursive", "walk the rules again for every bag"),
+            Strategy::new("memoized", "count every color once in a graph of the rules"),
+        ]
+    }
+
+    fn set_strategy(&mut self, name: &str) -> crate::result::Result<()> {
+        self.solver = match name {
+            "recursive" => Solver::Recursive,
+            "memoized" => Solver::Memoized,
+            _ => return Err(unknown_strategy(Self::DAY, name)),
+        };
+        Ok(())
+    }
+
+    fn queries(&self) -> Vec<Query> {
+        vec![
+            Query::new("contains", "<color>", "list the bags that can hold a bag"),
+            Query::new("inside", "<color>", "count the bags inside a bag"),
+        ]
+    }
+
+    fn query(
+        &self,
+        entries: &Self::Input,
+        _state: &mut QueryState,
+        name: &str,
+        args: &str,
+    ) -> crate::result::Result<String> {
+        let color = args.trim().trim_matches('"');
+        let known = entries
+            .iter()
+            .any(|rule| rule.color == color || rule.bags.iter().any(|bag| bag.1 == color));
+        if !known && (name == "contains" || name == "inside") {
+            return Err(format!("no rule mentions `{}` bags", color).into());
+        }
+
+        match name {
+            "contains" => {
+                let holders = containers(entries, color);
+                Ok(format!(
+                    "{} bags can contain {}: {}",
+                    holders.len(),
+                    color,
+                    holders.join(", ")
+                ))
+            }
+            "inside" => {
+                let mut bag_count = 0;
+                add_bags(entries, &mut bag_count, color);
+                Ok(format!("{} bags hold {} other bags", color, bag_count))
+            }
+            _ => Err(unknown_query(Self::DAY, name)),
+        }
+    }
+}
+
+/// colors of every bag that eventually holds a `color` bag, sorted by name.
+fn containers<'a>(entries: &'a [Rule], color: &str) -> Vec<&'a str> {
+    let mut found: Vec<&str> = vec![];
+    loop {
+        let before = found.len();
+        for rule in entries {
+            let holds = rule
+                .bags
+                .iter()
+                .any(|bag| bag.1 == color || found.contains(&bag.1.as_str()));
+            if holds && !found.contains(&rule.color.as_str()) {
+                found.push(&rule.color);
+            }
+        }
+        if found.len() == before {
+            break;
+        }
+    }
+    found.sort_unstable();
+    found
+}
+
+/// a distinct bag color for every `idx`, like `shiny gold`.
+fn color_name(idx: usize) -> String {
+    const ADJECTIVES: [&str; 16] = [
+        "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "muted",
+        "pale", "plaid", "posh", "shiny", "striped", "vibrant",
+    ];
+    const HUES: [&str; 16] = [
+        "aqua", "beige", "black", "blue", "bronze", "coral", "crimson", "gold", "gray", "green",
+        "indigo", "lime", "olive", "plum", "red", "teal",
+    ];
+    let adjective = ADJECTIVES[idx % ADJECTIVES.len()];
+    let hue = HUES[idx / ADJECTIVES.len() % HUES.len()];
+    match idx / (ADJECTIVES.len() * HUES.len()) {
+        0 => format!("{} {}", adjective, hue),
+        round => format!("{}{} {}", adjective, round, hue),
+    }
+}
+
+/// the rules keyed by the color of the outer bag.
+struct Graph<'a> {
+    rules: HashMap<&'a str, &'a [(usize, String)]>,
+}
+
+impl<'a> Graph<'a> {
+    fn new(entries: &'a [Rule]) -> Self {
+        let rules = entries
+            .iter()
+            .map(|rule| (rule.color.as_str(), rule.bags.as_slice()))
+            .collect();
+        Self { rules }
+    }
+
+    /// how many colors eventually hold a `color` bag.
+    fn holders(&self, color: &str) -> usize {
+        let mut memo = HashMap::new();
+        self.rules
+            .keys()
+            .filter(|outer| self.holds(outer, color, &mut memo))
+            .count()
+    }
+
+    fn holds(&self, outer: &'a str, color: &str, memo: &mut HashMap<&'a str, bool>) -> bool {
+        if let Some(holds) = memo.get(outer) {
+            return *holds;
+        }
+        let holds = self.rules.get(outer).is_some_and(|bags| {
+            bags.iter()
+                .any(|bag| bag.1 == color || self.holds(&bag.1, color, memo))
+        });
+        memo.insert(outer, holds);
+        holds
+    }
+
+    /// how many bags a `color` bag holds, counting the bags inside those too.
+    fn inside(&self, color: &'a str, memo: &mut HashMap<&'a str, usize>) -> usize {
+        if let Some(count) = memo.get(color) {
+            return *count;
+        }
+        let count = self.rules.get(color).map_or(0, |bags| {
+            bags.iter()
+                .map(|bag| bag.0 * (1 + self.inside(&bag.1, memo)))
+                .sum()
+        });
+        memo.insert(color, count);
+        count
     }
 }
 
 fn add_bags(entries: &[Rule], bag_count: &mut usize, color: &str) {
-    match entries.iter().find(|f| f.color == color) {
-        Some(rule) => {
-            for bag in &rule.bags {
-                *bag_count += bag.0;
-                for _ in 0..bag.0 {
-                    add_bags(entries, bag_count, &bag.1);
-                }
+    if let Some(rule) = entries.iter().find(|f| f.color == color) {
+        for bag in &rule.bags {
+            *bag_count += bag.0;
+            for _ in 0..bag.0 {
+                add_bags(entries, bag_count, &bag.1);
             }
         }
-        None => {}
     }
 }
 
@@ -106,38 +518,147 @@ fn can_contain(bags_that_can: &HashSet<String>, entries: &[Rule], bag_color: &st
         }
     }
 
-    return false;
+    false
 }
 
 #[cfg(test)]
 mod test {
-    use crate::PuzzleRunner;
+    use crate::{gen::assert_round_trip, repl::QueryState, PuzzleRunner};
 
     use super::{Puzzle, Rule};
 
     #[test]
     fn test_parse_rule() {
-        let rule: String = "light red bags contain 1 bright white bag, 2 muted yellow bags.".into();
+        let rule = "light red bags contain 1 bright white bag, 2 muted yellow bags.";
         assert_eq!(
             Rule {
                 color: "light red".into(),
                 bags: vec![(1, "bright white".into()), (2, "muted yellow".into())]
             },
-            Rule::from(rule)
+            rule.parse().unwrap()
+        );
+
+        let rule = "faded blue bags contain no other bags.";
+        assert_eq!(
+            Rule {
+                color: "faded blue".into(),
+                bags: vec![]
+            },
+            rule.parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_rule_errors() {
+        let err = |input: &str| input.parse::<Rule>().unwrap_err();
+        assert_eq!(
+            "24: expected bag count",
+            err("light red bags contain x bright white bag.").to_string()
+        );
+        assert_eq!(
+            "42: expected `.` at the end of the rule",
+            err("light red bags contain 1 bright white bag").to_string()
+        );
+        assert_eq!(
+            "1: expected `<color> bags contain`",
+            err("light red bags hold 1 bright white bag.").to_string()
+        );
+        assert_eq!(
+            "26: expected `bag` or `bags` after the color",
+            err("light red bags contain 1 bright white.").to_string()
+        );
+    }
+
+    #[test]
+    fn test_duplicate_rules() {
+        let input = "faded blue bags contain no other bags.\n\
+                     shiny gold bags contain 1 faded blue bag.\n\
+                     faded blue bags contain 2 shiny gold bags.\n";
+        assert_eq!(
+            "day7:3:1: `faded blue` already has a rule, the first one is on line 1",
+            Puzzle::default().parse_str(input).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_cyclic_rules() {
+        let input = "shiny gold bags contain 1 dark red bag.\n\
+                     dark red bags contain 1 shiny gold bag.\n";
+        assert_eq!(
+            "day7:2:25: `shiny gold` bags end up inside themselves, \
+             bags cannot hold each other in a loop",
+            Puzzle::default().parse_str(input).unwrap_err().to_string()
+        );
+
+        let input = "light red bags contain 2 muted yellow bags, 1 light red bag.\n\
+                     muted yellow bags contain no other bags.\n";
+        assert_eq!(
+            "day7:1:47: `light red` bags end up inside themselves, \
+             bags cannot hold each other in a loop",
+            Puzzle::default().parse_str(input).unwrap_err().to_string()
         );
     }
 
     #[test]
     fn test_part_one() {
-        let puzzle = Puzzle;
+        let puzzle = Puzzle::default();
         let entries = puzzle.test_input().unwrap();
-        assert_eq!(4, puzzle.part_one(&entries));
+        assert_eq!(4, puzzle.part_one(&entries).unwrap());
     }
 
     #[test]
     fn test_part_two() {
-        let puzzle = Puzzle;
+        let puzzle = Puzzle::default();
         let entries = puzzle.test_input().unwrap();
-        assert_eq!(32, puzzle.part_two(&entries));
+        assert_eq!(32, puzzle.part_two(&entries, None).unwrap());
+    }
+
+    #[test]
+    fn test_queries() {
+        let puzzle = Puzzle::default();
+        let entries = puzzle.test_input().unwrap();
+        let mut state = QueryState::default();
+        let mut query = |name, args| puzzle.query(&entries, &mut state, name, args);
+        assert_eq!(
+            "2 bags can contain bright white: dark orange, light red",
+            query("contains", "\"bright white\"").unwrap()
+        );
+        assert_eq!(
+            "shiny gold bags hold 32 other bags",
+            query("inside", "shiny gold").unwrap()
+        );
+        assert!(query("contains", "plaid").is_err());
+        assert!(query("paint", "shiny gold").is_err());
+    }
+
+    #[test]
+    fn test_color_param() {
+        let mut puzzle = Puzzle::default();
+        puzzle.set_param("color", "dark olive").unwrap();
+        let entries = puzzle.test_input().unwrap();
+        assert_eq!(5, puzzle.part_one(&entries).unwrap());
+        assert_eq!(7, puzzle.part_two(&entries, None).unwrap());
+        assert!(puzzle.set_param("color", " ").is_err());
+    }
+
+    #[test]
+    fn test_memoized_strategy() {
+        let mut puzzle = Puzzle::default();
+        puzzle.set_strategy("memoized").unwrap();
+        let entries = puzzle.test_input().unwrap();
+        let first = puzzle.part_one(&entries).unwrap();
+        assert_eq!(4, first);
+        assert_eq!(32, puzzle.part_two(&entries, Some(&first)).unwrap());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for rule in [
+            "light red bags contain 1 bright white bag, 2 muted yellow bags.",
+            "faded blue bags contain no other bags.",
+        ] {
+            assert_eq!(rule, rule.parse::<Rule>().unwrap().to_string());
+        }
+        assert_round_trip(&Puzzle::default());
     }
 }
@@ -1,21 +1,50 @@
-use crate::{io::read_lines, PuzzleRunner};
+use std::{fmt, str::FromStr};
 
-#[derive(Debug, Clone)]
+use crate::{
+    gen::{Generated, Rng, ATTEMPTS},
+    io::{parse_lines, write_lines},
+    params::parse_value,
+    repl::{unknown_query, Query, QueryState},
+    result::{Error, ParseError},
+    PuzzleRunner,
+};
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Instruction {
     Nop(isize),
     Acc(isize),
     Jmp(isize),
 }
 
-impl From<String> for Instruction {
-    fn from(input: String) -> Self {
-        let parts: Vec<&str> = input.split(" ").collect();
-        let val = parts[1].parse().unwrap();
-        match parts[0] {
-            "nop" => Instruction::Nop(val),
-            "acc" => Instruction::Acc(val),
-            "jmp" => Instruction::Jmp(val),
-            _ => panic!("invalid instruction"),
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> std::result::Result<Self, Self::Err> {
+        let (opcode, val) = input
+            .split_once(' ')
+            .ok_or_else(|| ParseError::end_of(input, "expected `<opcode> <offset>`"))?;
+        let val = val.parse().map_err(|_| {
+            ParseError::within(input, val, "invalid offset")
+                .with_hint("expected a signed number like `+4`")
+        })?;
+        match opcode {
+            "nop" => Ok(Instruction::Nop(val)),
+            "acc" => Ok(Instruction::Acc(val)),
+            "jmp" => Ok(Instruction::Jmp(val)),
+            _ => Err(
+                ParseError::within(input, opcode, format!("unknown opcode `{}`", opcode))
+                    .with_hint("expected nop/acc/jmp"),
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Nop(offset) => write!(f, "nop {:+}", offset),
+            Instruction::Acc(offset) => write!(f, "acc {:+}", offset),
+            Instruction::Jmp(offset) => write!(f, "jmp {:+}", offset),
         }
     }
 }
@@ -28,97 +57,337 @@ impl PuzzleRunner for Puzzle {
     type Second = isize;
     type Input = Vec<Instruction>;
 
-    fn parse_input(&self, filename: &str) -> crate::result::Result<Self::Input> {
-        Ok(read_lines(filename)?
-            .into_iter()
-            .map(Instruction::from)
-            .collect())
+    fn parse_str(&self, input: &str) -> crate::result::Result<Self::Input> {
+        parse_lines(Self::DAY, input, str::parse)
     }
 
-    fn part_one(&self, entries: &Self::Input) -> Self::First {
+    fn part_one(&self, entries: &Self::Input) -> crate::result::Result<Self::First> {
+        let terminates = || {
+            Error::NoSolution("the program terminates without running any instruction twice".into())
+        };
         let mut executed = vec![];
-        let mut acc = 0;
-        let mut idx = 0;
+        let mut acc: isize = 0;
+        let mut idx: usize = 0;
         while !executed.contains(&idx) {
             executed.push(idx);
-            match entries[idx] {
+            let instruction = entries.get(idx).ok_or_else(terminates)?;
+            match instruction {
                 Instruction::Nop(_) => idx += 1,
                 Instruction::Acc(a) => {
-                    acc += a;
+                    acc = acc.checked_add(*a).ok_or_else(|| {
+                        format!("the accumulator overflows at instruction {}", idx)
+                    })?;
                     idx += 1
                 }
-                Instruction::Jmp(j) => idx = (idx as isize + j) as usize,
+                // jumping before the first instruction leaves the program too
+                Instruction::Jmp(j) => idx = idx.checked_add_signed(*j).ok_or_else(terminates)?,
             }
         }
 
-        acc
+        Ok(acc)
     }
 
-    fn part_two(&self, entries: &Self::Input) -> Self::Second {
+    fn part_two(
+        &self,
+        entries: &Self::Input,
+        _first: Option<&Self::First>,
+    ) -> crate::result::Result<Self::Second> {
         for (idx, instr) in entries.iter().enumerate() {
             match instr {
                 Instruction::Acc(_) => {}
                 Instruction::Jmp(i) => {
                     let mut instructions = entries.clone();
-                    instructions[idx] = Instruction::Nop(i.clone());
-                    match run(&instructions) {
-                        Some(acc) => return acc,
-                        None => {}
+                    instructions[idx] = Instruction::Nop(*i);
+                    if let Some(acc) = run(&instructions) {
+                        return Ok(acc);
                     }
                 }
                 Instruction::Nop(i) => {
                     let mut instructions = entries.clone();
-                    instructions[idx] = Instruction::Jmp(i.clone());
-                    match run(&instructions) {
-                        Some(acc) => return acc,
-                        None => {}
+                    instructions[idx] = Instruction::Jmp(*i);
+                    if let Some(acc) = run(&instructions) {
+                        return Ok(acc);
                     }
                 }
             }
         }
 
-        unreachable!("should have a solution");
+        Err(Error::NoSolution(
+            "no single nop/jmp swap makes the program terminate".into(),
+        ))
+    }
+
+    fn write_input(&self, entries: &Self::Input) -> crate::result::Result<String> {
+        Ok(write_lines(entries))
+    }
+
+    /// a program of `size` instructions that terminates once its one corrupt instruction is
+    /// fixed. The fixed program only jumps forward along the path it takes, and every `nop` on
+    /// that path points back to an earlier instruction on it, so only one flip ends the loop.
+    fn generate(&self, rng: &mut Rng, size: usize) -> crate::result::Result<Generated> {
+        let size = size.max(1);
+        for _ in 0..ATTEMPTS {
+            let mut program = vec![];
+            let mut path: Vec<usize> = vec![];
+            while program.len() < size {
+                let idx = program.len();
+                path.push(idx);
+                match rng.index(4) {
+                    0 | 1 => program.push(Instruction::Acc(rng.between(-50, 50) as isize)),
+                    2 => {
+                        let earlier = *rng.choose(&path);
+                        program.push(Instruction::Nop(earlier as isize - idx as isize));
+                    }
+                    _ => {
+                        let offset = rng.between(1, 4.min(size - idx) as i64) as usize;
+                        program.push(Instruction::Jmp(offset as isize));
+                        // instructions skipped over only lead back onto the path
+                        for skipped in idx + 1..idx + offset {
+                            program.push(match rng.chance(50) {
+                                true => Instruction::Acc(rng.between(-50, 50) as isize),
+                                false => {
+                                    let earlier = *rng.choose(&path);
+                                    Instruction::Jmp(earlier as isize - skipped as isize)
+                                }
+                            });
+                        }
+                    }
+                }
+            }
+
+            let nops: Vec<usize> = path
+                .iter()
+                .copied()
+                .filter(|idx| matches!(program[*idx], Instruction::Nop(_)))
+                .collect();
+            if nops.is_empty() {
+                continue;
+            }
+            let corrupt = *rng.choose(&nops);
+            let acc = |until: usize| -> isize {
+                path.iter()
+                    .take_while(|idx| **idx < until)
+                    .map(|idx| match program[*idx] {
+                        Instruction::Acc(acc) => acc,
+                        _ => 0,
+                    })
+                    .sum()
+            };
+            let (first, second) = (acc(corrupt), acc(size));
+            if let Instruction::Nop(offset) = program[corrupt] {
+                program[corrupt] = Instruction::Jmp(offset);
+            }
+
+            return Ok(Generated::new(write_lines(&program), first, second));
+        }
+        Err("could not plant a corrupt instruction".into())
+    }
+
+    fn queries(&self) -> Vec<Query> {
+        vec![
+            Query::new(
+                "step",
+                "[count]",
+                "run the next instructions of the program",
+            ),
+            Query::new("reset", "", "go back to the start of the program"),
+        ]
+    }
+
+    fn query(
+        &self,
+        entries: &Self::Input,
+        state: &mut QueryState,
+        name: &str,
+        args: &str,
+    ) -> crate::result::Result<String> {
+        let machine: &mut Machine = state.get();
+        match name {
+            "step" => {
+                let count = match args {
+                    "" => 1,
+                    count => parse_value("count", count, "a number of instructions")?,
+                };
+                Ok(machine.step(entries, count))
+            }
+            "reset" => {
+                *machine = Machine::default();
+                Ok("back at instruction 0, acc 0".into())
+            }
+            _ => Err(unknown_query(Self::DAY, name)),
+        }
     }
 }
 
-fn run(instructions: &[Instruction]) -> Option<isize> {
+/// where the program stepped through from the REPL currently is.
+#[derive(Default)]
+struct Machine {
+    idx: usize,
+    acc: isize,
+    executed: Vec<usize>,
+}
+
+impl Machine {
+    /// runs up to `count` instructions, describing each one. Stops early when the program
+    /// terminates or an instruction is about to run a second time.
+    fn step(&mut self, instructions: &[Instruction], count: usize) -> String {
+        let mut lines = vec![];
+        for _ in 0..count {
+            let instruction = match instructions.get(self.idx) {
+                Some(_) if self.executed.contains(&self.idx) => {
+                    lines.push(format!(
+                        "instruction {} is about to run a second time, acc {}",
+                        self.idx, self.acc
+                    ));
+                    break;
+                }
+                Some(instruction) => instruction,
+                None => {
+                    lines.push(format!(
+                        "the program stopped at instruction {}, acc {}",
+                        self.idx, self.acc
+                    ));
+                    break;
+                }
+            };
+
+            let next = match instruction {
+                Instruction::Nop(_) => Some((self.idx + 1, self.acc)),
+                Instruction::Acc(a) => self.acc.checked_add(*a).map(|acc| (self.idx + 1, acc)),
+                Instruction::Jmp(j) => self.idx.checked_add_signed(*j).map(|idx| (idx, self.acc)),
+            };
+            let (next, acc) = match next {
+                Some(next) => next,
+                None => {
+                    lines.push(format!(
+                        "{:>4}: {} overflows, acc {}",
+                        self.idx, instruction, self.acc
+                    ));
+                    break;
+                }
+            };
+            lines.push(format!(
+                "{:>4}: {} -> next {}, acc {}",
+                self.idx, instruction, next, acc
+            ));
+            self.executed.push(self.idx);
+            self.idx = next;
+            self.acc = acc;
+        }
+        lines.join("\n")
+    }
+}
+
+/// runs the program until it terminates by stepping right past its last instruction, returning
+/// the accumulator. Returns `None` when an instruction is about to run twice, a jump leaves the
+/// program or the accumulator overflows.
+pub fn run(instructions: &[Instruction]) -> Option<isize> {
     let mut executed = vec![];
-    let mut acc = 0;
-    let mut idx = 0;
+    let mut acc: isize = 0;
+    let mut idx: usize = 0;
     while !executed.contains(&idx) {
         executed.push(idx);
         if idx == instructions.len() {
             return Some(acc);
         }
 
-        match instructions[idx] {
+        match instructions.get(idx)? {
             Instruction::Nop(_) => idx += 1,
             Instruction::Acc(a) => {
-                acc += a;
+                acc = acc.checked_add(*a)?;
                 idx += 1
             }
-            Instruction::Jmp(j) => idx = (idx as isize + j) as usize,
+            Instruction::Jmp(j) => idx = idx.checked_add_signed(*j)?,
         }
     }
 
-    return None;
+    None
 }
+
 #[cfg(test)]
 mod test {
-    use super::Puzzle;
-    use crate::PuzzleRunner;
+    use super::{run, Instruction, Puzzle};
+    use crate::{gen::assert_round_trip, repl::QueryState, PuzzleRunner};
+
+    #[test]
+    fn test_parse_instruction() {
+        assert_eq!(Instruction::Jmp(-4), "jmp -4".parse().unwrap());
+        assert_eq!(Instruction::Acc(3), "acc +3".parse().unwrap());
+        assert_eq!(
+            "1: unknown opcode `jnz`, expected nop/acc/jmp",
+            "jnz +4".parse::<Instruction>().unwrap_err().to_string()
+        );
+        assert_eq!(
+            "5: invalid offset, expected a signed number like `+4`",
+            "nop four".parse::<Instruction>().unwrap_err().to_string()
+        );
+    }
 
     #[test]
     fn test_part_one() {
         let puzzle = Puzzle;
         let entries = puzzle.test_input().unwrap();
-        assert_eq!(5, puzzle.part_one(&entries));
+        assert_eq!(5, puzzle.part_one(&entries).unwrap());
     }
 
     #[test]
     fn test_part_two() {
         let puzzle = Puzzle;
         let entries = puzzle.test_input().unwrap();
-        assert_eq!(8, puzzle.part_two(&entries));
+        assert_eq!(8, puzzle.part_two(&entries, None).unwrap());
+    }
+
+    #[test]
+    fn test_step() {
+        let puzzle = Puzzle;
+        let entries = puzzle.test_input().unwrap();
+        let mut state = QueryState::default();
+        assert_eq!(
+            "   0: nop +0 -> next 1, acc 0\n   1: acc +1 -> next 2, acc 1",
+            puzzle.query(&entries, &mut state, "step", "2").unwrap()
+        );
+        let steps = puzzle.query(&entries, &mut state, "step", "10").unwrap();
+        assert!(steps.ends_with("instruction 1 is about to run a second time, acc 5"));
+        puzzle.query(&entries, &mut state, "reset", "").unwrap();
+        assert_eq!(
+            "   0: nop +0 -> next 1, acc 0",
+            puzzle.query(&entries, &mut state, "step", "").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let puzzle = Puzzle;
+        let entries = puzzle
+            .parse_str("acc +9223372036854775807\nacc +1\njmp -2\n")
+            .unwrap();
+        assert_eq!(
+            "the accumulator overflows at instruction 1",
+            puzzle.part_one(&entries).unwrap_err().to_string()
+        );
+        assert_eq!(None, run(&entries));
+        let mut state = QueryState::default();
+        assert_eq!(
+            "   0: acc +9223372036854775807 -> next 1, acc 9223372036854775807\n   \
+             1: acc +1 overflows, acc 9223372036854775807",
+            puzzle.query(&entries, &mut state, "step", "3").unwrap()
+        );
+
+        let entries = puzzle.parse_str("nop +0\njmp -2\n").unwrap();
+        assert!(puzzle.part_one(&entries).is_err());
+        assert_eq!(None, run(&entries));
+        assert_eq!(0, puzzle.part_two(&entries, None).unwrap());
+        let mut state = QueryState::default();
+        assert!(puzzle
+            .query(&entries, &mut state, "step", "3")
+            .unwrap()
+            .ends_with("1: jmp -2 overflows, acc 0"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!("jmp -4", Instruction::Jmp(-4).to_string());
+        assert_eq!("nop +0", Instruction::Nop(0).to_string());
+        assert_round_trip(&Puzzle);
     }
 }
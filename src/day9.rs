@@ -1,4 +1,10 @@
-use crate::{io::read_lines, PuzzleRunner};
+use crate::{
+    gen::{Generated, Rng, ATTEMPTS},
+    io::{parse_lines, write_lines},
+    params::{invalid_value, parse_value, unknown_param, Param},
+    result::{Error, ParseError},
+    PuzzleRunner,
+};
 
 pub struct Puzzle {
     preamble_size: usize,
@@ -10,77 +16,262 @@ impl Puzzle {
     }
 }
 
+impl Default for Puzzle {
+    fn default() -> Self {
+        Self::new(25)
+    }
+}
+
 impl PuzzleRunner for Puzzle {
     const DAY: usize = 9;
     type First = i64;
     type Second = i64;
     type Input = Vec<i64>;
+    const NEEDS_FIRST: bool = true;
 
-    fn parse_input(&self, filename: &str) -> crate::result::Result<Self::Input> {
-        Ok(read_lines(filename)?
-            .into_iter()
-            .map(|c| c.parse().unwrap())
-            .collect())
+    fn parse_str(&self, input: &str) -> crate::result::Result<Self::Input> {
+        parse_lines(Self::DAY, input, |n| {
+            n.parse().map_err(|_| {
+                ParseError::within(n, n, "invalid number").with_hint("expected an integer")
+            })
+        })
     }
 
-    fn part_one(&self, entries: &Self::Input) -> Self::First {
+    fn part_one(&self, entries: &Self::Input) -> crate::result::Result<Self::First> {
         'outer: for (idx, number) in entries.iter().enumerate() {
             if idx < self.preamble_size {
                 continue;
             }
 
+            // sums that overflow cannot be the number, so `checked_add` skips them
             let preamble = &entries[idx - self.preamble_size..idx];
             for j in preamble.iter() {
                 for k in preamble.iter() {
-                    if &(j + k) == number && j != k {
+                    if j.checked_add(*k) == Some(*number) && j != k {
                         continue 'outer;
                     }
                 }
             }
 
-            // is my input broken? this returns 1497 which is not accepted as the solution but it
-            // clearly satisfies the conditions. The second number that satisfies it is 144381670,
-            // which is accepted as the solution.
-            return number.clone();
+            return Ok(*number);
         }
 
-        unreachable!("should have a solution");
+        Err(Error::NoSolution(
+            "every number is the sum of two of the previous ones".into(),
+        ))
     }
 
-    fn part_two(&self, entries: &Self::Input) -> Self::Second {
-        let number = 144381670;
-        'outer: for i in 0..entries.len() {
-            for j in 0..entries.len() {
-                let numbers = entries.iter().skip(i).take(j);
-                let sum = numbers.clone().fold(0, |acc, n| acc + n);
-                if sum == number {
-                    return numbers.clone().max().unwrap() + numbers.min().unwrap();
-                } else if sum > number {
-                    continue 'outer;
+    fn part_two(
+        &self,
+        entries: &Self::Input,
+        first: Option<&Self::First>,
+    ) -> crate::result::Result<Self::Second> {
+        let number = match first {
+            Some(first) => *first,
+            None => self.part_one(entries)?,
+        };
+        for start in 0..entries.len() {
+            let mut sum: i64 = 0;
+            for (end, n) in entries.iter().enumerate().skip(start) {
+                sum = sum.checked_add(*n).ok_or_else(|| {
+                    format!(
+                        "the sum of the numbers {} to {} overflows",
+                        start + 1,
+                        end + 1
+                    )
+                })?;
+                if sum > number {
+                    break;
+                }
+
+                // a range needs at least two numbers, or the invalid number would be its own range
+                if end > start && sum == number {
+                    let range = &entries[start..=end];
+                    if let (Some(min), Some(max)) = (range.iter().min(), range.iter().max()) {
+                        return Ok(min + max);
+                    }
                 }
             }
         }
 
-        unreachable!("should have a solution");
+        Err(Error::NoSolution(format!(
+            "no contiguous range of at least two numbers sums to {}",
+            number
+        )))
     }
+
+    fn write_input(&self, entries: &Self::Input) -> crate::result::Result<String> {
+        Ok(write_lines(entries))
+    }
+
+    /// numbers that are each the sum of two of the oldest numbers before them, apart from one
+    /// near the end, which is instead the sum of a run of earlier numbers. The numbers grow
+    /// quickly, so inputs only get so long before they overflow.
+    fn generate(&self, rng: &mut Rng, size: usize) -> crate::result::Result<Generated> {
+        let preamble = self.preamble_size;
+        if size <= preamble {
+            return Err(format!(
+                "day 9 inputs need more numbers than the preamble of {}",
+                preamble
+            )
+            .into());
+        }
+
+        for _ in 0..ATTEMPTS {
+            let mut numbers: Vec<i64> = (1..=preamble as i64 * 3).collect();
+            rng.shuffle(&mut numbers);
+            numbers.truncate(preamble);
+
+            // the invalid number is after the preamble, so the run before it has two numbers
+            let lowest = preamble.max(size - 1 - size / 10);
+            let invalid = rng.between(lowest as i64, size as i64 - 1) as usize;
+            if !extend_valid(rng, &mut numbers, invalid, preamble)? {
+                continue;
+            }
+
+            let start = rng.index(invalid - 1);
+            let len = rng.between(2, 10.min(invalid - start) as i64) as usize;
+            let run = &numbers[start..start + len];
+            let (min, max) = (run.iter().min().unwrap(), run.iter().max().unwrap());
+            let (weakness, target) = (min + max, run.iter().sum::<i64>());
+
+            let window = &numbers[invalid - preamble..];
+            let is_sum = window
+                .iter()
+                .any(|a| window.iter().any(|b| a != b && a + b == target));
+            if is_sum {
+                continue;
+            }
+            numbers.push(target);
+            if !extend_valid(rng, &mut numbers, size, preamble)? {
+                continue;
+            }
+
+            // the run has to be the first one summing to the target, and as the numbers are
+            // positive, at most one run starts at each number
+            let first_run = (0..=start).find(|from| {
+                let mut sum = 0;
+                numbers[*from..]
+                    .iter()
+                    .map(|n| {
+                        sum += n;
+                        sum
+                    })
+                    .take_while(|sum| *sum <= target)
+                    .any(|sum| sum == target)
+            });
+            if first_run != Some(start) {
+                continue;
+            }
+
+            let lines: Vec<String> = numbers.iter().map(i64::to_string).collect();
+            return Ok(Generated::new(lines.join("\n") + "\n", target, weakness));
+        }
+        Err("could not plant an invalid number".into())
+    }
+
+    fn params(&self) -> Vec<Param> {
+        vec![Param::new(
+            "preamble",
+            "how many previous numbers each number is checked against",
+            self.preamble_size,
+        )]
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> crate::result::Result<()> {
+        let expected = "an integer of at least 2";
+        match name {
+            "preamble" => match parse_value(name, value, expected)? {
+                size if size >= 2 => self.preamble_size = size,
+                _ => return Err(invalid_value(name, value, expected)),
+            },
+            _ => return Err(unknown_param(Self::DAY, name)),
+        }
+        Ok(())
+    }
+}
+
+/// adds numbers that are the sum of two different numbers among the oldest few of the preamble
+/// before them, until there are `len`. Returns whether no preamble was made of a single number.
+fn extend_valid(
+    rng: &mut Rng,
+    numbers: &mut Vec<i64>,
+    len: usize,
+    preamble: usize,
+) -> crate::result::Result<bool> {
+    while numbers.len() < len {
+        let window = &numbers[numbers.len() - preamble..];
+        let oldest = &window[..preamble.min(6)];
+        let pair = (0..ATTEMPTS)
+            .map(|_| (*rng.choose(oldest), *rng.choose(oldest)))
+            .find(|(a, b)| a != b)
+            .or_else(|| {
+                let a = window[0];
+                window.iter().find(|b| **b != a).map(|b| (a, *b))
+            });
+        let (a, b) = match pair {
+            Some(pair) => pair,
+            None => return Ok(false),
+        };
+        let sum = a.checked_add(b).ok_or_else(|| {
+            format!(
+                "numbers overflow after {} of them with a preamble of {}",
+                numbers.len(),
+                preamble
+            )
+        })?;
+        numbers.push(sum);
+    }
+    Ok(true)
 }
 
 #[cfg(test)]
 mod test {
     use super::Puzzle;
-    use crate::PuzzleRunner;
+    use crate::{gen::assert_round_trip, PuzzleRunner};
 
     #[test]
     fn test_part_one() {
         let puzzle = Puzzle::new(5);
         let input = puzzle.test_input().unwrap();
-        assert_eq!(127, puzzle.part_one(&input));
+        assert_eq!(127, puzzle.part_one(&input).unwrap());
     }
 
     #[test]
     fn test_part_two() {
         let puzzle = Puzzle::new(5);
         let input = puzzle.test_input().unwrap();
-        assert_eq!(62, dbg!(puzzle.part_two(&input)));
+        assert_eq!(62, puzzle.part_two(&input, Some(&127)).unwrap());
+    }
+
+    #[test]
+    fn test_part_two_short_ranges() {
+        let puzzle = Puzzle::new(2);
+        let input = puzzle.parse_str("1\n2\n0\n").unwrap();
+        assert_eq!(0, puzzle.part_one(&input).unwrap());
+        assert!(puzzle.part_two(&input, Some(&0)).is_err());
+
+        let input = puzzle.parse_str("1\n2\n5\n").unwrap();
+        assert!(puzzle.part_two(&input, Some(&5)).is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let puzzle = Puzzle::new(2);
+        let input = puzzle
+            .parse_str("9223372036854775807\n1\n-9223372036854775808\n")
+            .unwrap();
+        assert_eq!(i64::MIN, puzzle.part_one(&input).unwrap());
+
+        let input = puzzle.parse_str("-9223372036854775808\n-1\n").unwrap();
+        assert_eq!(
+            "the sum of the numbers 1 to 2 overflows",
+            puzzle.part_two(&input, Some(&0)).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        assert_round_trip(&Puzzle::default());
     }
 }
@@ -0,0 +1,2 @@
+// inputs and answers compiled into the binary, generated by the build script from `data`
+include!(concat!(env!("OUT_DIR"), "/embedded.rs"));
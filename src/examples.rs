@@ -0,0 +1,220 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    io::data_dir,
+    params::{self, Assignment},
+    registry::{self, SolveContext},
+    result::Result,
+};
+
+/// directory with one `dayN` directory of example cases per day, `examples` in the data
+/// directory.
+pub fn examples_dir() -> PathBuf {
+    Path::new(&data_dir()).join("examples")
+}
+
+/// an example input from the puzzle text along with the answers it should give. Case files start
+/// with a header of `part1: answer`, `part2: answer` and `param: name=value` lines, ended by a
+/// `---` line, and the input follows verbatim.
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    pub day: usize,
+    pub name: String,
+    pub params: Vec<Assignment>,
+    /// expected answers for part one and two, when the puzzle text gives them
+    pub expected: [Option<String>; 2],
+    pub input: String,
+}
+
+impl Example {
+    pub fn parse(day: usize, name: &str, text: &str) -> Result<Self> {
+        let mut example = Example {
+            day,
+            name: name.to_string(),
+            params: vec![],
+            expected: [None, None],
+            input: String::new(),
+        };
+
+        let mut rest = text;
+        loop {
+            let (line, remaining) = rest.split_once('\n').unwrap_or((rest, ""));
+            if line.trim_end() == "---" {
+                example.input = remaining.to_string();
+                return Ok(example);
+            }
+            if remaining.is_empty() {
+                return Err(format!("example {}: missing `---` after the header", name).into());
+            }
+            rest = remaining;
+
+            let (key, value) = line.split_once(':').ok_or_else(|| {
+                format!("example {}: expected `key: value`, found `{}`", name, line)
+            })?;
+            let value = value.trim();
+            match key.trim() {
+                "part1" => example.expected[0] = Some(value.to_string()),
+                "part2" => example.expected[1] = Some(value.to_string()),
+                "param" => example.params.push(value.parse()?),
+                other => {
+                    return Err(format!(
+                        "example {}: unknown header `{}`, expected part1, part2 or param",
+                        name, other
+                    )
+                    .into())
+                }
+            }
+        }
+    }
+
+    /// the text of the case file, as `parse` reads it.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (part, expected) in self.expected.iter().enumerate() {
+            if let Some(expected) = expected {
+                text += &format!("part{}: {}\n", part + 1, expected);
+            }
+        }
+        for param in &self.params {
+            match param.day {
+                Some(day) => text += &format!("param: day{}.{}={}\n", day, param.name, param.value),
+                None => text += &format!("param: {}={}\n", param.name, param.value),
+            }
+        }
+        text + "---\n" + &self.input
+    }
+
+    /// writes the case to its `dayN` directory under `dir`, numbering its name when a case by
+    /// that name already exists. Returns the path written to.
+    pub fn save(&mut self, dir: &Path) -> Result<PathBuf> {
+        let day_dir = dir.join(format!("day{}", self.day));
+        fs::create_dir_all(&day_dir)?;
+        let base = self.name.clone();
+        let mut number = 1;
+        while day_dir.join(format!("{}.txt", self.name)).exists() {
+            number += 1;
+            self.name = format!("{}_{}", base, number);
+        }
+
+        let path = day_dir.join(format!("{}.txt", self.name));
+        fs::write(&path, self.to_text())?;
+        Ok(path)
+    }
+
+    /// reads the case at `path`, taking the day from its `dayN` directory and the name of the
+    /// case from the file name.
+    pub fn read(path: &Path) -> Result<Self> {
+        let day = path
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|dir| dir.to_str()?.strip_prefix("day")?.parse().ok())
+            .ok_or_else(|| format!("{} is not in a `dayN` directory", path.display()))?;
+        let name = path
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| format!("{} has no case name", path.display()))?;
+        Self::parse(day, name, &fs::read_to_string(path)?)
+    }
+
+    /// solves `part` of the example with its parameters applied.
+    pub fn solve(&self, part: usize) -> Result<String> {
+        let mut puzzles = vec![registry::puzzle(self.day)?];
+        params::apply(&mut puzzles, &self.params)?;
+        let puzzle = &puzzles[0];
+        let input = puzzle.parse_str(&self.input)?;
+        puzzle.solve(part, input.as_ref(), &mut SolveContext::default())
+    }
+
+    /// checks `part` against its expected answer, failing when they differ.
+    pub fn check(&self, part: usize) -> Result<()> {
+        let expected = self.expected[part - 1]
+            .as_deref()
+            .ok_or_else(|| format!("no expected answer for part {}", part))?;
+        match self.solve(part)? {
+            answer if answer == expected => Ok(()),
+            answer => Err(format!("expected {}, got {}", expected, answer).into()),
+        }
+    }
+}
+
+/// every example case under `dir`, ordered by day and then by name. Only the given day is read
+/// when `day` is set.
+pub fn discover(dir: &Path, day: Option<usize>) -> Result<Vec<Example>> {
+    let mut examples = vec![];
+    if !dir.exists() {
+        return Ok(examples);
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let day_dir = entry?.path();
+        if !day_dir.is_dir() {
+            continue;
+        }
+        for case in fs::read_dir(&day_dir)? {
+            let case = case?.path();
+            if case.extension().is_some_and(|ext| ext == "txt") {
+                let example = Example::read(&case)?;
+                if day.is_none_or(|day| day == example.day) {
+                    examples.push(example);
+                }
+            }
+        }
+    }
+
+    examples.sort_by(|a, b| (a.day, &a.name).cmp(&(b.day, &b.name)));
+    Ok(examples)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{discover, examples_dir, Example};
+    use crate::params::Assignment;
+
+    #[test]
+    fn test_parse_example() {
+        let example =
+            Example::parse(9, "small", "part2: 62\nparam: preamble=5\n---\n1\n2\n").unwrap();
+        assert_eq!(9, example.day);
+        assert_eq!([None, Some("62".to_string())], example.expected);
+        assert_eq!(
+            vec!["preamble=5".parse::<Assignment>().unwrap()],
+            example.params
+        );
+        assert_eq!("1\n2\n", example.input);
+
+        assert!(Example::parse(9, "small", "part2: 62\n1\n2\n").is_err());
+        assert!(Example::parse(9, "small", "part3: 62\n---\n").is_err());
+    }
+
+    #[test]
+    fn test_to_text() {
+        let text = "part2: 62\nparam: preamble=5\n---\n1\n2\n";
+        let example = Example::parse(9, "small", text).unwrap();
+        assert_eq!(text, example.to_text());
+    }
+
+    #[test]
+    fn test_check() {
+        let example = Example::parse(
+            1,
+            "small",
+            "part1: 514579\npart2: 1\n---\n1721\n299\n366\n675\n979\n",
+        )
+        .unwrap();
+        assert!(example.check(1).is_ok());
+        assert_eq!(
+            "expected 1, got 241861950",
+            example.check(2).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_discover() {
+        let examples = discover(&examples_dir(), Some(4)).unwrap();
+        assert!(!examples.is_empty());
+        assert!(examples.iter().all(|e| e.day == 4));
+    }
+}
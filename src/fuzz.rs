@@ -0,0 +1,212 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    examples::{self, examples_dir},
+    gen::Rng,
+    pool,
+    registry::{self, DynPuzzle},
+    result::Result,
+};
+
+/// characters that tend to trip up parsers: multi byte ones, invisible ones and control ones.
+const UNICODE: [char; 10] = [
+    'é', '€', '𝄞', '\u{200b}', '\u{feff}', '\0', '\t', '\r', 'İ', '٣',
+];
+
+/// fragments of puzzle syntax, which get mutations past the first checks of a parser.
+const TOKENS: [&str; 14] = [
+    "-",
+    "+",
+    ":",
+    " ",
+    "\n",
+    "\n\n",
+    "#",
+    ",",
+    ".",
+    "0",
+    "99999999999999999999",
+    " bags",
+    " contain ",
+    "no other bags",
+];
+
+/// how the parser of a day misbehaved on an input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Failure {
+    Panic(String),
+    /// the parser did not return within the timeout
+    Hang,
+}
+
+/// a mutated input the parser of a day failed on.
+#[derive(Debug, PartialEq)]
+pub struct Finding {
+    pub day: usize,
+    pub input: String,
+    pub failure: Failure,
+}
+
+/// applies between one and four random mutations to `input`.
+pub fn mutate(rng: &mut Rng, input: &str) -> String {
+    let mut input = input.to_string();
+    for _ in 0..rng.between(1, 4) {
+        let boundaries: Vec<usize> = input
+            .char_indices()
+            .map(|(idx, _)| idx)
+            .chain(std::iter::once(input.len()))
+            .collect();
+        let at = *rng.choose(&boundaries);
+        let mut lines: Vec<&str> = input.split_inclusive('\n').collect();
+        input = match rng.index(6) {
+            0 if !input.is_empty() => {
+                let mut bytes = input.into_bytes();
+                let idx = rng.index(bytes.len());
+                bytes[idx] ^= 1 << rng.index(8);
+                String::from_utf8_lossy(&bytes).into_owned()
+            }
+            1 if !lines.is_empty() => {
+                lines.remove(rng.index(lines.len()));
+                lines.concat()
+            }
+            2 if !lines.is_empty() => {
+                let idx = rng.index(lines.len());
+                lines.insert(idx, lines[idx]);
+                lines.concat()
+            }
+            3 => input[..at].to_string(),
+            4 => format!("{}{}{}", &input[..at], rng.choose(&UNICODE), &input[at..]),
+            _ => format!("{}{}{}", &input[..at], rng.choose(&TOKENS), &input[at..]),
+        };
+    }
+    input
+}
+
+/// parses `input` as day `day`, catching panics and giving up after `timeout`.
+pub fn parse_guarded(day: usize, input: &str, timeout: Duration) -> Result<Option<Failure>> {
+    let puzzle = registry::puzzle(day)?;
+    let input = input.to_string();
+    Ok(guarded(timeout, move || {
+        // an error is the expected outcome for most mutations, only panics are findings
+        let _ = puzzle.parse_str(&input);
+    }))
+}
+
+/// runs `f` on its own thread, reporting whether it panicked or did not return within
+/// `timeout`. A thread that hangs is left running, as threads cannot be stopped.
+pub fn guarded<F: FnOnce() + Send + 'static>(timeout: Duration, f: F) -> Option<Failure> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(f)));
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(Ok(())) => None,
+        Ok(Err(payload)) => Some(Failure::Panic(pool::panic_message(&*payload))),
+        Err(_) => Some(Failure::Hang),
+    }
+}
+
+/// the inputs mutations start from: a generated input along with the examples of the day.
+pub fn corpus(puzzle: &dyn DynPuzzle, rng: &mut Rng) -> Result<Vec<String>> {
+    let mut corpus: Vec<String> = examples::discover(&examples_dir(), Some(puzzle.day()))?
+        .into_iter()
+        .map(|example| example.input)
+        .collect();
+    if let Ok(generated) = puzzle.generate(rng, 30) {
+        corpus.push(generated.input);
+    }
+    if corpus.is_empty() {
+        return Err(format!("day {} has no inputs to mutate", puzzle.day()).into());
+    }
+    Ok(corpus)
+}
+
+/// parses `iterations` mutated inputs with the parser of `puzzle`, returning the ones it
+/// panicked or hung on.
+pub fn fuzz(
+    puzzle: &dyn DynPuzzle,
+    rng: &mut Rng,
+    iterations: usize,
+    timeout: Duration,
+) -> Result<Vec<Finding>> {
+    let corpus = corpus(puzzle, rng)?;
+    let mut findings = vec![];
+    for _ in 0..iterations {
+        let original = rng.choose(&corpus);
+        let input = mutate(rng, original);
+        if let Some(failure) = parse_guarded(puzzle.day(), &input, timeout)? {
+            findings.push(Finding {
+                day: puzzle.day(),
+                input,
+                failure,
+            });
+        }
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{fuzz, guarded, mutate, parse_guarded, Failure, Finding};
+    use crate::{
+        gen::{is_no_generator, Rng},
+        registry::puzzles,
+    };
+
+    #[test]
+    fn test_mutate() {
+        let mut rng = Rng::new(1);
+        let input = "nop +0\nacc +1\njmp -2\n";
+        let mutants: Vec<String> = (0..50).map(|_| mutate(&mut rng, input)).collect();
+        assert!(mutants.iter().any(|mutant| mutant != input));
+        assert_eq!(mutants, {
+            let mut rng = Rng::new(1);
+            (0..50).map(|_| mutate(&mut rng, input)).collect::<Vec<_>>()
+        });
+    }
+
+    #[test]
+    fn test_parse_guarded() {
+        let timeout = Duration::from_secs(5);
+        assert_eq!(None, parse_guarded(5, "FBFBBFFRLR\n", timeout).unwrap());
+        assert_eq!(None, parse_guarded(5, "FBFB€BFFRLR\n", timeout).unwrap());
+        assert!(parse_guarded(26, "", timeout).is_err());
+    }
+
+    #[test]
+    fn test_guarded() {
+        let timeout = Duration::from_millis(100);
+        assert_eq!(None, guarded(timeout, || {}));
+        assert_eq!(
+            Some(Failure::Panic("index out of range".into())),
+            guarded(timeout, || panic!("index out of range"))
+        );
+        assert_eq!(
+            Some(Failure::Hang),
+            guarded(timeout, || std::thread::sleep(Duration::from_secs(1)))
+        );
+    }
+
+    #[test]
+    fn test_parsers_do_not_panic() {
+        for puzzle in puzzles() {
+            let mut rng = Rng::new(puzzle.day() as u64);
+            // new days have nothing to mutate until they get a generator
+            if let Err(err) = puzzle.generate(&mut rng, 30) {
+                if is_no_generator(&err, puzzle.day()) {
+                    continue;
+                }
+            }
+            let findings = fuzz(puzzle.as_ref(), &mut rng, 200, Duration::from_secs(5)).unwrap();
+            assert_eq!(Vec::<Finding>::new(), findings);
+        }
+    }
+}
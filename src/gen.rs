@@ -0,0 +1,170 @@
+use std::fmt;
+
+use crate::result::Error;
+#[cfg(test)]
+use crate::{
+    examples::{self, examples_dir},
+    fuzz::mutate,
+    PuzzleRunner,
+};
+
+/// small seeded pseudo random number generator (SplitMix64), so generated inputs are the same
+/// on every platform for the same seed.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// a number in `0..len`, which must not be empty.
+    pub fn index(&mut self, len: usize) -> usize {
+        assert!(len > 0, "cannot pick from an empty range");
+        ((self.next_u64() as u128 * len as u128) >> 64) as usize
+    }
+
+    /// a number between `low` and `high`, both included.
+    pub fn between(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low <= high, "empty range {}..={}", low, high);
+        let span = (high as i128 - low as i128 + 1) as u128;
+        (low as i128 + ((self.next_u64() as u128 * span) >> 64) as i128) as i64
+    }
+
+    /// true `percent` times out of a hundred.
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.index(100) < percent
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            items.swap(idx, self.index(idx + 1));
+        }
+    }
+}
+
+/// a generated input along with the answers planted in it.
+#[derive(Debug, PartialEq)]
+pub struct Generated {
+    pub input: String,
+    pub answers: [String; 2],
+}
+
+impl Generated {
+    pub fn new(input: String, first: impl fmt::Display, second: impl fmt::Display) -> Self {
+        Self {
+            input,
+            answers: [first.to_string(), second.to_string()],
+        }
+    }
+
+    /// the input as an example case, with the answers in its header.
+    pub fn to_example(&self) -> String {
+        format!(
+            "part1: {}\npart2: {}\n---\n{}",
+            self.answers[0], self.answers[1], self.input
+        )
+    }
+}
+
+pub fn no_generator(day: usize) -> Error {
+    format!("day {} has no input generator", day).into()
+}
+
+/// whether `err` is the one days without a generator of their own fail with.
+pub fn is_no_generator(err: &Error, day: usize) -> bool {
+    err.to_string() == no_generator(day).to_string()
+}
+
+/// how many attempts a generator makes at planting an answer before giving up.
+pub const ATTEMPTS: usize = 1000;
+
+/// checks that `puzzle` writes inputs back as text it parses into the same input, for its
+/// examples and generated inputs of many seeds and sizes, along with mutations of them that still
+/// parse.
+#[cfg(test)]
+pub fn assert_round_trip<P: PuzzleRunner>(puzzle: &P)
+where
+    P::Input: PartialEq + fmt::Debug,
+{
+    let mut rng = Rng::new(P::DAY as u64);
+    let examples = examples::discover(&examples_dir(), Some(P::DAY)).unwrap();
+    let mut inputs: Vec<P::Input> = examples
+        .iter()
+        .map(|example| puzzle.parse_str(&example.input).unwrap())
+        .collect();
+    for seed in 0..20 {
+        let generated = puzzle
+            .generate(&mut Rng::new(seed), 60 + seed as usize * 10)
+            .unwrap();
+        let mutants = (0..10).map(|_| mutate(&mut rng, &generated.input));
+        for text in mutants.chain(std::iter::once(generated.input.clone())) {
+            inputs.extend(puzzle.parse_str(&text));
+        }
+    }
+
+    for input in inputs {
+        let written = puzzle.write_input(&input).unwrap();
+        let parsed = puzzle.parse_str(&written).unwrap();
+        assert_eq!(input, parsed, "day {} wrote {:?}", P::DAY, written);
+        assert_eq!(written, puzzle.write_input(&parsed).unwrap());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_no_generator, Rng};
+    use crate::registry::{puzzles, SolveContext};
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(2020);
+        let first: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+        let mut again = Rng::new(2020);
+        assert_eq!(first, (0..4).map(|_| again.next_u64()).collect::<Vec<_>>());
+        assert_ne!(first[0], Rng::new(2021).next_u64());
+
+        for _ in 0..1000 {
+            assert!((-3..=3).contains(&rng.between(-3, 3)));
+            assert!(rng.index(7) < 7);
+        }
+        assert_eq!(i64::MAX, rng.between(i64::MAX, i64::MAX));
+    }
+
+    #[test]
+    fn test_planted_answers() {
+        for puzzle in puzzles() {
+            for seed in 0..5 {
+                let generated = match puzzle.generate(&mut Rng::new(seed), 60) {
+                    Err(err) if is_no_generator(&err, puzzle.day()) => break,
+                    generated => generated.unwrap(),
+                };
+                let input = puzzle.parse_str(&generated.input).unwrap();
+                let mut context = SolveContext::default();
+                for part in 1..=2 {
+                    assert_eq!(
+                        generated.answers[part - 1],
+                        puzzle.solve(part, input.as_ref(), &mut context).unwrap(),
+                        "day {} part {} seed {}",
+                        puzzle.day(),
+                        part,
+                        seed
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::Path,
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{bench::Report, io::read_lines, result::Result};
+
+/// file benchmark runs are recorded in, one line per day and part.
+pub const HISTORY_FILE: &str = "./bench_history.txt";
+
+/// median time of one part in one benchmark run. Runs are told apart by their timestamp.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// milliseconds since the unix epoch when the run started
+    pub timestamp: u128,
+    /// git revision the run was made on, when it could be determined
+    pub revision: Option<String>,
+    pub day: usize,
+    pub part: usize,
+    pub median: Duration,
+}
+
+impl Record {
+    /// the records for both parts of a benchmark report.
+    pub fn from_report(report: &Report, timestamp: u128, revision: Option<&str>) -> Vec<Record> {
+        let parts = [(1, &report.part_one), (2, &report.part_two)];
+        parts
+            .iter()
+            .map(|(part, stats)| Record {
+                timestamp,
+                revision: revision.map(String::from),
+                day: report.day,
+                part: *part,
+                median: stats.median,
+            })
+            .collect()
+    }
+
+    /// parses a `timestamp revision day part median_ns` line, with `-` for a missing revision.
+    pub fn parse(line: &str) -> Option<Record> {
+        let mut fields = line.split_whitespace();
+        let timestamp = fields.next()?.parse().ok()?;
+        let revision = match fields.next()? {
+            "-" => None,
+            revision => Some(revision.to_string()),
+        };
+        let day = fields.next()?.parse().ok()?;
+        let part = fields.next()?.parse().ok()?;
+        let median = Duration::from_nanos(fields.next()?.parse().ok()?);
+        if fields.next().is_some() {
+            return None;
+        }
+
+        Some(Record {
+            timestamp,
+            revision,
+            day,
+            part,
+            median,
+        })
+    }
+
+    pub fn to_line(&self) -> String {
+        format!(
+            "{} {} {} {} {}",
+            self.timestamp,
+            self.revision.as_deref().unwrap_or("-"),
+            self.day,
+            self.part,
+            self.median.as_nanos()
+        )
+    }
+}
+
+/// reads every record in `filename`, oldest first. A missing file is an empty history.
+pub fn read(filename: &str) -> Result<Vec<Record>> {
+    if !Path::new(filename).exists() {
+        return Ok(vec![]);
+    }
+
+    read_lines(filename)?
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            Record::parse(line).ok_or_else(|| {
+                format!(
+                    "{}:{}: expected `timestamp revision day part median_ns`",
+                    filename,
+                    idx + 1
+                )
+                .into()
+            })
+        })
+        .collect()
+}
+
+pub fn append(filename: &str, records: &[Record]) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(filename)?;
+    for record in records {
+        writeln!(file, "{}", record.to_line())?;
+    }
+    Ok(())
+}
+
+pub fn now() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis())
+}
+
+/// short hash of the checked out git revision, marked with `+` when there are local changes.
+pub fn revision() -> Option<String> {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let revision = git(&["rev-parse", "--short", "HEAD"])?;
+    match git(&["status", "--porcelain", "--untracked-files=no"]) {
+        Some(changes) if !changes.is_empty() => Some(format!("{}+", revision)),
+        _ => Some(revision),
+    }
+}
+
+/// which earlier run the latest one is compared against.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Baseline {
+    /// the most recent earlier record of each day and part
+    #[default]
+    Previous,
+    /// the latest run whose revision starts with, or whose timestamp is, the given value
+    Run(String),
+}
+
+/// timing of one part in the latest run next to the one it is compared against.
+#[derive(Debug, PartialEq)]
+pub struct Comparison {
+    pub day: usize,
+    pub part: usize,
+    pub before: Duration,
+    pub after: Duration,
+}
+
+impl Comparison {
+    /// how much slower the part got, in percent. Negative when it got faster.
+    pub fn change(&self) -> f64 {
+        let before = self.before.as_nanos() as f64;
+        (self.after.as_nanos() as f64 - before) / before.max(1.0) * 100.0
+    }
+
+    pub fn regressed(&self, threshold: f64) -> bool {
+        self.change() > threshold
+    }
+}
+
+/// compares the parts of the latest run in `records` against `baseline`. Parts the baseline
+/// has no timing for are left out.
+pub fn compare(records: &[Record], baseline: &Baseline) -> Result<Vec<Comparison>> {
+    let latest = records
+        .last()
+        .ok_or("the benchmark history is empty")?
+        .timestamp;
+    let (earlier, current): (Vec<&Record>, Vec<&Record>) =
+        records.iter().partition(|r| r.timestamp != latest);
+
+    let earlier: Vec<&Record> = match baseline {
+        Baseline::Previous => earlier,
+        Baseline::Run(run) => {
+            let matches = |r: &Record| {
+                r.timestamp.to_string() == *run
+                    || r.revision
+                        .as_deref()
+                        .is_some_and(|rev| rev.starts_with(run))
+            };
+            let timestamp = earlier
+                .iter()
+                .rev()
+                .find(|r| matches(r))
+                .ok_or_else(|| format!("no earlier benchmark run matches `{}`", run))?
+                .timestamp;
+            earlier
+                .into_iter()
+                .filter(|r| r.timestamp == timestamp)
+                .collect()
+        }
+    };
+
+    Ok(current
+        .iter()
+        .filter_map(|record| {
+            let before = earlier
+                .iter()
+                .rev()
+                .find(|r| r.day == record.day && r.part == record.part)?;
+            Some(Comparison {
+                day: record.day,
+                part: record.part,
+                before: before.median,
+                after: record.median,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{compare, Baseline, Record};
+
+    fn record(timestamp: u128, revision: &str, day: usize, micros: u64) -> Record {
+        Record {
+            timestamp,
+            revision: Some(revision.into()),
+            day,
+            part: 1,
+            median: Duration::from_micros(micros),
+        }
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let line = "1608000000000 4a1b2c3 9 2 35482";
+        let record = Record::parse(line).unwrap();
+        assert_eq!(Some("4a1b2c3".into()), record.revision);
+        assert_eq!(Duration::from_nanos(35482), record.median);
+        assert_eq!(line, record.to_line());
+
+        assert_eq!(
+            None,
+            Record::parse("1608000000000 - 9 2 35482").unwrap().revision
+        );
+        assert!(Record::parse("1608000000000 - 9 2").is_none());
+        assert!(Record::parse("1608000000000 - 9 2 fast").is_none());
+    }
+
+    #[test]
+    fn test_compare_previous() {
+        let records = vec![
+            record(1, "aaa", 1, 100),
+            record(1, "aaa", 2, 100),
+            record(2, "bbb", 2, 200),
+            record(3, "ccc", 1, 150),
+            record(3, "ccc", 2, 100),
+        ];
+        let comparisons = compare(&records, &Baseline::Previous).unwrap();
+        assert_eq!(2, comparisons.len());
+        assert_eq!(Duration::from_micros(100), comparisons[0].before);
+        assert!(comparisons[0].regressed(10.0));
+        assert!(!comparisons[0].regressed(50.0));
+        assert_eq!(Duration::from_micros(200), comparisons[1].before);
+        assert_eq!(-50.0, comparisons[1].change());
+    }
+
+    #[test]
+    fn test_compare_baseline() {
+        let records = vec![
+            record(1, "aaa", 1, 100),
+            record(2, "bbb", 1, 200),
+            record(3, "ccc", 1, 150),
+        ];
+        let comparisons = compare(&records, &Baseline::Run("aa".into())).unwrap();
+        assert_eq!(50.0, comparisons[0].change());
+        let comparisons = compare(&records, &Baseline::Run("2".into())).unwrap();
+        assert_eq!(-25.0, comparisons[0].change());
+        assert!(compare(&records, &Baseline::Run("ccc".into())).is_err());
+        assert!(compare(&[], &Baseline::Previous).is_err());
+    }
+}
@@ -1,10 +1,164 @@
 use std::{
-    fs::File,
-    io::{BufRead, Result},
+    fmt::Display,
+    fs::{self, File},
+    io::{BufRead, Read},
 };
 
-pub fn read_lines(filename: &str) -> Result<Vec<String>> {
+use crate::result::{Error, ParseError, Result};
+
+/// directory the inputs and answers are read from: `AOC_DATA_DIR` when it is set, `./data`
+/// otherwise.
+pub fn data_dir() -> String {
+    std::env::var("AOC_DATA_DIR").unwrap_or_else(|_| "./data".into())
+}
+
+/// the text of `filename`, of stdin when it is `-`, or of `default` when no file is given.
+pub fn read_input(filename: Option<&str>, default: &str) -> Result<String> {
+    match filename {
+        Some("-") => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+        Some(filename) => Ok(fs::read_to_string(filename)?),
+        None => Ok(fs::read_to_string(default)?),
+    }
+}
+
+pub fn read_lines(filename: &str) -> std::io::Result<Vec<String>> {
     let file = File::open(filename)?;
     let reader = std::io::BufReader::new(file);
     reader.lines().collect()
 }
+
+/// parses every line of `input` with `parse`, tagging errors with `day` and the line they were
+/// found in.
+pub fn parse_lines<T, F>(day: usize, input: &str, parse: F) -> Result<Vec<T>>
+where
+    F: Fn(&str) -> std::result::Result<T, ParseError>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| parse(line).map_err(|e| e.at(day, idx + 1, line)))
+        .collect()
+}
+
+/// writes every item on a line of its own, the inverse of `parse_lines`.
+pub fn write_lines<T: Display>(items: &[T]) -> String {
+    items.iter().map(|item| format!("{}\n", item)).collect()
+}
+
+/// writes records of one or more lines separated by blank lines, as days that group their
+/// lines into records read them. A record without lines is written as an extra blank line.
+pub fn write_records<T: Display>(records: &[T]) -> String {
+    let records: Vec<String> = records
+        .iter()
+        .map(|record| match record.to_string() {
+            record if record.is_empty() => record,
+            record => record + "\n",
+        })
+        .collect();
+    records.join("\n")
+}
+
+pub fn no_writer(day: usize) -> Error {
+    format!("day {} cannot write its input back as text", day).into()
+}
+
+/// renders `err` like a compiler diagnostic: parse errors show the offending line of
+/// `filename` with the bad text underlined, any other error is a single line. I/O errors name
+/// the file, as a missing input usually means the data directory is not where it is expected.
+pub fn render_error(err: &Error, filename: &str) -> String {
+    let (day, line, column, len, message, hint, text) = match err {
+        Error::Parse {
+            day,
+            line,
+            column,
+            len,
+            message,
+            hint,
+            text,
+        } => (day, line, column, len, message, hint, text),
+        Error::Io(err) => return format!("error: {}: {}", filename, err),
+        _ => return format!("error: {}", err),
+    };
+
+    let number = line.to_string();
+    let gutter = " ".repeat(number.len());
+    // keep tabs so the caret lines up with the text above it
+    let indent: String = text
+        .chars()
+        .take(column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .chain(std::iter::repeat(' '))
+        .take(column - 1)
+        .collect();
+
+    let mut out = format!("error[day{}]: {}\n", day, message);
+    out += &format!("{}--> {}:{}:{}\n", gutter, filename, line, column);
+    out += &format!("{} |\n", gutter);
+    out += &format!("{} | {}\n", number, text);
+    out += &format!("{} | {}{}", gutter, indent, "^".repeat(*len));
+    if let Some(hint) = hint {
+        out += &format!(" {}", hint);
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::{read_input, render_error};
+    use crate::result::{Error, ParseError};
+
+    #[test]
+    fn test_read_input() {
+        let input = read_input(Some("./data/day8_test.txt"), "./data/day9.txt").unwrap();
+        assert!(input.starts_with("nop +0\n"));
+        assert_eq!(input, read_input(None, "./data/day8_test.txt").unwrap());
+        assert!(read_input(Some("./data/day0.txt"), "./data/day8_test.txt").is_err());
+    }
+
+    #[test]
+    fn test_render_parse_error() {
+        let err = ParseError::within("jnz +4", &"jnz +4"[..3], "unknown opcode `jnz`")
+            .with_hint("expected nop/acc/jmp")
+            .at(8, 12, "jnz +4");
+        assert_eq!(
+            "error[day8]: unknown opcode `jnz`
+  --> data/day8.txt:12:1
+   |
+12 | jnz +4
+   | ^^^ expected nop/acc/jmp",
+            render_error(&err, "data/day8.txt")
+        );
+    }
+
+    #[test]
+    fn test_render_past_end_of_line() {
+        let err = ParseError::end_of("1-3 a:", "expected a password").at(2, 1, "1-3 a:");
+        assert_eq!(
+            "error[day2]: expected a password
+ --> in.txt:1:7
+  |
+1 | 1-3 a:
+  |       ^",
+            render_error(&err, "in.txt")
+        );
+    }
+
+    #[test]
+    fn test_render_other_error() {
+        let err = Error::NoSolution("no two entries sum to 2020".into());
+        assert_eq!(
+            "error: no solution: no two entries sum to 2020",
+            render_error(&err, "in.txt")
+        );
+
+        let err = Error::Io(std::io::ErrorKind::NotFound.into());
+        assert_eq!(
+            "error: in.txt: entity not found",
+            render_error(&err, "in.txt")
+        );
+    }
+}
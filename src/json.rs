@@ -0,0 +1,126 @@
+use std::fmt;
+
+/// minimal JSON value, only used for writing machine readable output.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    String(String),
+    /// fields are written in insertion order
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn object() -> Self {
+        Value::Object(vec![])
+    }
+
+    /// adds a field to an object, panics when called on any other kind of value.
+    pub fn with<V: Into<Value>>(mut self, key: &str, value: V) -> Self {
+        match &mut self {
+            Value::Object(fields) => fields.push((key.to_string(), value.into())),
+            _ => panic!("`with` called on a non object value"),
+        }
+        self
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Int(n)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Value::Int(n as i64)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Self {
+        v.map(Into::into).unwrap_or(Value::Null)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(n) => write!(f, "{}", n),
+            Value::String(s) => write_string(f, s),
+            Value::Object(fields) => {
+                write!(f, "{{")?;
+                for (idx, (key, value)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+#[cfg(test)]
+mod test {
+    use super::Value;
+
+    #[test]
+    fn test_write_object() {
+        let value = Value::object()
+            .with("day", 7usize)
+            .with("answer", "224")
+            .with("ok", true)
+            .with("missing", None::<i64>)
+            .with("nested", Value::object().with("n", -1i64));
+        assert_eq!(
+            r#"{"day":7,"answer":"224","ok":true,"missing":null,"nested":{"n":-1}}"#,
+            value.to_string()
+        );
+    }
+
+    #[test]
+    fn test_escape_string() {
+        let value = Value::from("a \"quoted\"\\path\n\u{1}");
+        assert_eq!(r#""a \"quoted\"\\path\n\u0001""#, value.to_string());
+    }
+}
@@ -0,0 +1,142 @@
+pub mod answers;
+pub mod bench;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+#[cfg(feature = "embed")]
+pub mod embedded;
+pub mod examples;
+pub mod fuzz;
+pub mod gen;
+pub mod history;
+pub mod io;
+pub mod json;
+pub mod memory;
+pub mod minimize;
+pub mod params;
+pub mod pool;
+pub mod registry;
+pub mod repl;
+pub mod report;
+pub mod result;
+pub mod run;
+pub mod scaffold;
+pub mod strategy;
+pub mod verify;
+
+use std::io::BufRead;
+
+use crate::{
+    gen::{no_generator, Generated, Rng},
+    io::no_writer,
+    params::{unknown_param, Param},
+    repl::{unknown_query, Query, QueryState},
+    result::Result,
+    strategy::{unknown_strategy, Strategy},
+};
+
+pub trait PuzzleRunner {
+    const DAY: usize;
+    /// output for the first part of the puzzle
+    type First: std::fmt::Display;
+    /// output for the second part of the puzzle
+    type Second: std::fmt::Display;
+    /// input for both parts of the puzzle, shared between threads when days run in parallel
+    type Input: Send + Sync;
+    /// whether part two builds on the answer of part one, in which case part one is solved
+    /// first and the two parts of the day never run in parallel
+    const NEEDS_FIRST: bool = false;
+
+    fn parse_str(&self, input: &str) -> Result<Self::Input>;
+
+    fn parse_reader(&self, mut reader: impl BufRead) -> Result<Self::Input> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        self.parse_str(&input)
+    }
+
+    fn parse_input(&self, filename: &str) -> Result<Self::Input> {
+        let file = std::fs::File::open(filename)?;
+        self.parse_reader(std::io::BufReader::new(file))
+    }
+    fn part_one(&self, entries: &Self::Input) -> Result<Self::First>;
+    /// solves part two, given the answer part one gave for the same input when it has been
+    /// solved already. Only days that set `NEEDS_FIRST` look at it.
+    fn part_two(&self, entries: &Self::Input, first: Option<&Self::First>) -> Result<Self::Second>;
+
+    /// parameters the puzzle can be tuned with, along with their current values.
+    fn params(&self) -> Vec<Param> {
+        vec![]
+    }
+
+    /// sets the parameter called `name` from its textual `value`, validating it.
+    fn set_param(&mut self, name: &str, _value: &str) -> Result<()> {
+        Err(unknown_param(Self::DAY, name))
+    }
+
+    /// named implementations of the parts, the first of which is used by default. Days with a
+    /// single implementation declare none.
+    fn strategies(&self) -> Vec<Strategy> {
+        vec![]
+    }
+
+    /// switches the parts over to the strategy called `name`.
+    fn set_strategy(&mut self, name: &str) -> Result<()> {
+        Err(unknown_strategy(Self::DAY, name))
+    }
+
+    /// a random input of about `size` records, along with the answers it was built to have.
+    fn generate(&self, _rng: &mut Rng, _size: usize) -> Result<Generated> {
+        Err(no_generator(Self::DAY))
+    }
+
+    /// the input as canonical puzzle text, which `parse_str` reads back into the same input.
+    fn write_input(&self, _input: &Self::Input) -> Result<String> {
+        Err(no_writer(Self::DAY))
+    }
+
+    /// day specific queries the REPL offers on the parsed input.
+    fn queries(&self) -> Vec<Query> {
+        vec![]
+    }
+
+    /// answers the query called `name`, given the rest of the command line as `args`.
+    fn query(
+        &self,
+        _entries: &Self::Input,
+        _state: &mut QueryState,
+        name: &str,
+        _args: &str,
+    ) -> Result<String> {
+        Err(unknown_query(Self::DAY, name))
+    }
+
+    #[cfg(test)]
+    fn test_input(&self) -> Result<Self::Input> {
+        let filename = format!("./data/day{}_test.txt", Self::DAY);
+        let entries = self.parse_input(&filename)?;
+        Ok(entries)
+    }
+
+    fn input_path(&self) -> String {
+        format!("{}/day{}.txt", io::data_dir(), Self::DAY)
+    }
+
+    fn input(&self) -> Result<Self::Input> {
+        #[cfg(feature = "embed")]
+        {
+            if let Some(input) = embedded::input(Self::DAY) {
+                return self.parse_str(input);
+            }
+        }
+
+        let entries = self.parse_input(&self.input_path())?;
+        Ok(entries)
+    }
+}
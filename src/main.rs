@@ -1,62 +1,370 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod io;
-mod result;
-
-use crate::result::Result;
-
-trait PuzzleRunner {
-    const DAY: usize;
-    /// output for the first part of the puzzle
-    type First: std::fmt::Display;
-    /// output for the second part of the puzzle
-    type Second: std::fmt::Display;
-    /// input for both parts of the puzzle
-    type Input;
-
-    fn parse_input(&self, filename: &str) -> Result<Self::Input>;
-    fn part_one(&self, entries: &Self::Input) -> Self::First;
-    fn part_two(&self, entries: &Self::Input) -> Self::Second;
-
-    #[cfg(test)]
-    fn test_input(&self) -> Result<Self::Input> {
-        let filename = format!("./data/day{}_test.txt", Self::DAY);
-        let entries = self.parse_input(&filename)?;
-        Ok(entries)
-    }
-
-    fn input(&self) -> Result<Self::Input> {
-        let filename = format!("./data/day{}.txt", Self::DAY);
-        let entries = self.parse_input(&filename)?;
-        Ok(entries)
-    }
-
-    fn run(&self) -> Result<()> {
-        let entries = self.input()?;
-        println!("\nday {} solution", Self::DAY);
-        println!("    part one: {}", self.part_one(&entries));
-        println!("    part two: {}", self.part_two(&entries));
-        Ok(())
+mod cli;
+
+use std::path::Path;
+
+use aoc2020::{
+    answers::Answers,
+    bench, examples,
+    fuzz::{self, Failure},
+    gen::Rng,
+    history::{self, Record},
+    io,
+    memory::{self, Usage},
+    minimize::{self, Goal},
+    pool,
+    registry::{self, DynPuzzle},
+    repl::Repl,
+    report::{self, Case, Report},
+    result::Result,
+    run::{self, Output},
+    scaffold, strategy, verify,
+};
+
+use crate::cli::{BenchOptions, Command, CompareOptions, MemoryOptions};
+
+#[cfg(feature = "count-allocs")]
+#[global_allocator]
+static ALLOCATOR: memory::Counting = memory::Counting;
+
+/// prints every parameter `puzzle` declares along with its current value, followed by its
+/// strategies.
+fn list_params(puzzle: &dyn DynPuzzle) {
+    println!("\nday {}", puzzle.day());
+    let params = puzzle.params();
+    if params.is_empty() {
+        println!("    no parameters");
+    }
+    for param in params {
+        println!("    {} = {}", param.name, param.value);
+        println!("        {}", param.description);
+    }
+    for (idx, strategy) in puzzle.strategies().iter().enumerate() {
+        let default = if idx == 0 { " (default)" } else { "" };
+        println!("    strategy {}{}", strategy.name, default);
+        println!("        {}", strategy.description);
     }
 }
 
-fn main() -> Result<()> {
-    day1::Puzzle.run()?;
-    day2::Puzzle.run()?;
-    day3::Puzzle.run()?;
-    day4::Puzzle.run()?;
-    day5::Puzzle.run()?;
-    day6::Puzzle.run()?;
-    day7::Puzzle.run()?;
-    day8::Puzzle.run()?;
-    day9::Puzzle::new(25).run()?;
+/// benchmarks `puzzle`, printing timing statistics for parsing and each part.
+fn bench(puzzle: &dyn DynPuzzle, options: &BenchOptions) -> Result<bench::Report> {
+    let report = bench::bench(puzzle, options.input.as_deref(), options.iterations)?;
+    println!(
+        "\nday {} ({} iterations)\n    {:<8} {:>12} {:>12} {:>12} {:>12}",
+        report.day, options.iterations, "", "min", "median", "p95", "max"
+    );
+    let rows = [
+        ("parse", &report.parse),
+        ("part one", &report.part_one),
+        ("part two", &report.part_two),
+    ];
+    for (name, stats) in rows.iter() {
+        println!(
+            "    {:<8} {:>12} {:>12} {:>12} {:>12}",
+            name,
+            format!("{:?}", stats.min),
+            format!("{:?}", stats.median),
+            format!("{:?}", stats.p95),
+            format!("{:?}", stats.max),
+        );
+    }
+    Ok(report)
+}
+
+/// compares the latest benchmark run against an earlier one, printing the change of every part.
+/// Returns the number of parts that got slower than the threshold allows.
+fn compare(options: &CompareOptions) -> Result<usize> {
+    let records = history::read(&options.history)?;
+    let comparisons = history::compare(&records, &options.baseline)?;
+    if comparisons.is_empty() {
+        println!("no earlier timings to compare against");
+    }
 
+    let mut regressions = 0;
+    for comparison in comparisons {
+        let regressed = comparison.regressed(options.threshold);
+        if regressed {
+            regressions += 1;
+        }
+        println!(
+            "day {} part {}: {:>12} -> {:>12} {:>+8.1}%{}",
+            comparison.day,
+            comparison.part,
+            format!("{:?}", comparison.before),
+            format!("{:?}", comparison.after),
+            comparison.change(),
+            if regressed { "  REGRESSION" } else { "" }
+        );
+    }
+    if regressions > 0 {
+        eprintln!(
+            "{} part(s) got more than {}% slower",
+            regressions, options.threshold
+        );
+    }
+    Ok(regressions)
+}
+
+/// reports the allocations made while parsing and solving `puzzle`.
+fn memory(puzzle: &dyn DynPuzzle, options: &MemoryOptions) -> Result<()> {
+    let report = memory::profile(puzzle, options.input.as_deref())?;
+    println!(
+        "\nday {}\n    {:<8} {:>12} {:>12} {:>12}",
+        report.day, "", "allocations", "bytes", "peak"
+    );
+    let rows = [
+        ("parse", &report.parse),
+        ("part one", &report.part_one),
+        ("part two", &report.part_two),
+    ];
+    for (name, usage) in rows.iter() {
+        let Usage {
+            allocations,
+            bytes,
+            peak,
+        } = usage;
+        println!(
+            "    {:<8} {:>12} {:>12} {:>12}",
+            name, allocations, bytes, peak
+        );
+    }
     Ok(())
 }
+
+/// prints `cases` in the `report` format, returning how many of them did not pass.
+fn print_cases(name: &str, cases: &[Case], report: Report) -> usize {
+    let rendered = report::render(name, cases, report);
+    if !rendered.is_empty() {
+        println!("{}", rendered);
+    }
+    cases.iter().filter(|case| !case.passed()).count()
+}
+
+/// runs `command`, returning how many days or parts failed.
+fn execute(command: Command) -> Result<usize> {
+    let mut failures = 0;
+    match command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::Run(options) => {
+            let puzzles = registry::configured(
+                options.day,
+                options.config.as_deref(),
+                &options.params,
+                options.strategy.as_deref(),
+            )?;
+            let input = options.input.as_deref();
+            pool::for_each_ordered(
+                &puzzles,
+                options.jobs,
+                |puzzle| run::run(puzzle.as_ref(), options.part, input, options.format),
+                |puzzle, output| {
+                    let output =
+                        output.unwrap_or_else(|message| Output::panicked(puzzle.day(), &message));
+                    print!("{}", output.stdout);
+                    eprint!("{}", output.stderr);
+                    failures += output.failures;
+                },
+            );
+        }
+        Command::Bench(options) => {
+            let puzzles = registry::configured(
+                options.day,
+                options.config.as_deref(),
+                &options.params,
+                options.strategy.as_deref(),
+            )?;
+            let (timestamp, revision) = (history::now(), history::revision());
+            let mut records = vec![];
+            for puzzle in puzzles {
+                // a failing day is reported and skipped, the timings of the others are still kept
+                match bench(puzzle.as_ref(), &options) {
+                    Ok(report) => {
+                        records.extend(Record::from_report(&report, timestamp, revision.as_deref()))
+                    }
+                    Err(err) => {
+                        eprintln!("day {} failed: {}", puzzle.day(), err);
+                        failures += 1;
+                    }
+                }
+            }
+
+            // timings of other inputs, parameters or strategies are not comparable with the usual
+            // ones
+            let custom = options.input.is_some()
+                || options.config.is_some()
+                || !options.params.is_empty()
+                || options.strategy.is_some();
+            if options.save && custom {
+                eprintln!(
+                    "not recording the run in the history, as it used a custom input, parameters or strategy"
+                );
+            } else if options.save && !records.is_empty() {
+                history::append(&options.history, &records)?;
+            }
+        }
+        Command::Compare(options) => failures += compare(&options)?,
+        Command::Crosscheck(options) => {
+            let puzzles = registry::configured(
+                options.day,
+                options.config.as_deref(),
+                &options.params,
+                None,
+            )?;
+            for mut puzzle in puzzles {
+                // with every day selected, the days with a single strategy have nothing to check
+                if options.day.is_none() && puzzle.strategies().len() < 2 {
+                    continue;
+                }
+                let input = puzzle.parse(options.input.as_deref())?;
+                for check in strategy::crosscheck(puzzle.as_mut(), input.as_ref())? {
+                    println!("{}", check);
+                    if !check.agrees() {
+                        failures += 1;
+                    }
+                }
+            }
+            if failures > 0 {
+                eprintln!(
+                    "{} part(s) got different answers from different strategies",
+                    failures
+                );
+            }
+        }
+        Command::Memory(options) => {
+            if !cfg!(feature = "count-allocs") {
+                return Err(
+                    "allocations are only counted when built with `--features count-allocs`".into(),
+                );
+            }
+            for puzzle in registry::selected(options.day)? {
+                memory(puzzle.as_ref(), &options)?;
+            }
+        }
+        Command::New(day) => {
+            for path in scaffold::new_day(Path::new("."), day)? {
+                println!("wrote {}", path);
+            }
+        }
+        Command::Examples(options) => {
+            let examples = examples::discover(&examples::examples_dir(), options.day)?;
+            let cases: Vec<Case> = examples.iter().flat_map(verify::check_example).collect();
+            failures += print_cases("examples", &cases, options.report);
+            if failures > 0 {
+                eprintln!("{} example part(s) failed", failures);
+            }
+        }
+        Command::Params(day) => {
+            for puzzle in registry::selected(day)? {
+                list_params(puzzle.as_ref());
+            }
+        }
+        Command::Gen(options) => {
+            let puzzle =
+                registry::configured(Some(options.day), None, &options.params, None)?.remove(0);
+            let generated = puzzle.generate(&mut Rng::new(options.seed), options.size)?;
+            if options.example {
+                print!("{}", generated.to_example());
+            } else {
+                print!("{}", generated.input);
+                eprintln!(
+                    "part 1: {}\npart 2: {}",
+                    generated.answers[0], generated.answers[1]
+                );
+            }
+        }
+        Command::Fuzz(options) => {
+            // panics are reported as findings, so the default message for each is only noise
+            let hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(|_| {}));
+            let mut rng = Rng::new(options.seed);
+            for puzzle in registry::selected(options.day)? {
+                let findings = fuzz::fuzz(
+                    puzzle.as_ref(),
+                    &mut rng,
+                    options.iterations,
+                    options.timeout,
+                )?;
+                println!(
+                    "day {}: {} inputs, {} failures",
+                    puzzle.day(),
+                    options.iterations,
+                    findings.len()
+                );
+                for finding in &findings {
+                    match &finding.failure {
+                        Failure::Panic(message) => println!("    panicked: {}", message),
+                        Failure::Hang => {
+                            println!("    did not return within {:?}", options.timeout)
+                        }
+                    }
+                    println!("        on {:?}", finding.input);
+                }
+                failures += findings.len();
+            }
+            std::panic::set_hook(hook);
+        }
+        Command::Minimize(options) => {
+            let mut puzzle =
+                registry::configured(Some(options.day), None, &options.params, None)?.remove(0);
+            let input = io::read_input(options.input.as_deref(), &puzzle.input_path())?;
+            let goal = match options.expected {
+                Some((part, answer)) => Goal::Expected(part, answer),
+                None => Goal::Disagree(options.part),
+            };
+            // strategies panicking on a shrunk input only make it uninteresting, not a failure
+            let hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(|_| {}));
+            let minimized = minimize::minimize_example(
+                puzzle.as_mut(),
+                &input,
+                &goal,
+                options.reference.as_deref(),
+                options.name,
+                options.params,
+            );
+            std::panic::set_hook(hook);
+            let (minimized, mut example) = minimized?;
+            for check in &minimized.checks {
+                println!("{}", check);
+            }
+            println!(
+                "shrunk {} lines to {}",
+                input.lines().count(),
+                example.input.lines().count()
+            );
+            let path = example.save(&examples::examples_dir())?;
+            println!("wrote {}", path.display());
+        }
+        Command::Repl(options) => {
+            let mut repl = Repl::new(registry::puzzle(options.day)?, options.input)?;
+            repl.run(std::io::stdin().lock(), std::io::stdout())?;
+        }
+        Command::Verify(options) => {
+            let answers = Answers::load(options.answers.as_deref())?;
+            let mut cases = vec![];
+            for puzzle in registry::selected(options.day)? {
+                let (verified, diagnostic) = verify::verify(puzzle.as_ref(), &answers);
+                if let (Some(diagnostic), Report::Text) = (diagnostic, options.report) {
+                    eprintln!("{}", diagnostic);
+                }
+                cases.extend(verified);
+            }
+            failures += print_cases("verify", &cases, options.report);
+            if failures > 0 {
+                eprintln!("{} part(s) did not match the expected answers", failures);
+            }
+        }
+    }
+
+    Ok(failures)
+}
+
+fn main() {
+    match cli::parse_args(std::env::args().skip(1)).and_then(execute) {
+        Ok(0) => {}
+        Ok(_) => std::process::exit(1),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
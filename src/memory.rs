@@ -0,0 +1,145 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    io::Read,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    registry::{DynPuzzle, SolveContext},
+    result::Result,
+};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// wraps the system allocator and counts every allocation made through it. Install it with
+/// `#[global_allocator]` to get meaningful numbers out of `measure`; the counters are global,
+/// so allocations from every thread are included.
+pub struct Counting;
+
+impl Counting {
+    fn record(&self, size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.record(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.record(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+            self.record(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// allocations made while running a piece of code.
+#[derive(Debug, Default, PartialEq)]
+pub struct Usage {
+    pub allocations: usize,
+    pub bytes: usize,
+    /// most bytes that were live at the same time, above what was live before
+    pub peak: usize,
+}
+
+/// runs `f`, returning its output along with the allocations it made.
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Usage) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = BYTES.load(Ordering::Relaxed);
+    let live = LIVE.load(Ordering::Relaxed);
+    PEAK.store(live, Ordering::Relaxed);
+
+    let output = f();
+    let usage = Usage {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes: BYTES.load(Ordering::Relaxed) - bytes,
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(live),
+    };
+    (output, usage)
+}
+
+pub struct Report {
+    pub day: usize,
+    pub parse: Usage,
+    pub part_one: Usage,
+    pub part_two: Usage,
+}
+
+/// measures the allocations made by parsing and by each part of `puzzle` separately.
+pub fn profile(puzzle: &dyn DynPuzzle, filename: Option<&str>) -> Result<Report> {
+    // read stdin up front, so the buffer it is read into is not counted as parsing
+    let stdin = match filename {
+        Some("-") => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            Some(input)
+        }
+        _ => None,
+    };
+
+    let (input, parse) = measure(|| match &stdin {
+        Some(input) => puzzle.parse_str(input),
+        None => puzzle.parse(filename),
+    });
+    let input = input?;
+    let mut context = SolveContext::default();
+    let (first, part_one) = measure(|| puzzle.solve(1, input.as_ref(), &mut context));
+    first?;
+    let (second, part_two) = measure(|| puzzle.solve(2, input.as_ref(), &mut context));
+    second?;
+
+    Ok(Report {
+        day: puzzle.day(),
+        parse,
+        part_one,
+        part_two,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{measure, Counting};
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    #[test]
+    fn test_measure() {
+        // tests run in parallel and share the counters, so only lower bounds hold
+        let (_, usage) = measure(|| {
+            let small = vec![1u8; 1000];
+            drop(small);
+            vec![1u8; 4000]
+        });
+        assert!(usage.allocations >= 2);
+        assert!(usage.bytes >= 5000);
+        assert!(usage.peak >= 4000);
+    }
+}
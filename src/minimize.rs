@@ -0,0 +1,284 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{
+    examples::Example,
+    params::Assignment,
+    registry::{DynPuzzle, SolveContext},
+    result::Result,
+    strategy::{self, Crosscheck},
+};
+
+/// what keeps an input worth shrinking.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Goal {
+    /// the strategies of the day disagree on the part, or on either part when `None`
+    Disagree(Option<usize>),
+    /// the day answers the part with something other than the given answer
+    Expected(usize, String),
+}
+
+/// a smallest input found that still meets the goal it was minimized for.
+#[derive(Debug, PartialEq)]
+pub struct Minimized {
+    pub input: String,
+    /// answers of every strategy on the minimized input, or the expected answer next to the
+    /// actual one
+    pub checks: Vec<Crosscheck>,
+}
+
+/// shrinks `items` to a smaller subsequence that is still `interesting`, by delta debugging:
+/// chunks of the items, and then everything but those chunks, are tried on their own, with
+/// the chunks getting smaller until no single item can be left out.
+pub fn ddmin<T: Clone>(items: &[T], mut interesting: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut items = items.to_vec();
+    let mut chunks = 2;
+    while items.len() >= 2 {
+        let size = items.len().div_ceil(chunks);
+        let starts: Vec<usize> = (0..items.len()).step_by(size).collect();
+
+        let subset = starts
+            .iter()
+            .map(|start| items[*start..(start + size).min(items.len())].to_vec())
+            .find(|subset| interesting(subset));
+        if let Some(subset) = subset {
+            items = subset;
+            chunks = 2;
+            continue;
+        }
+
+        let complement = starts
+            .iter()
+            .map(|start| {
+                let mut rest = items[..*start].to_vec();
+                rest.extend_from_slice(&items[(start + size).min(items.len())..]);
+                rest
+            })
+            .find(|complement| interesting(complement));
+        if let Some(complement) = complement {
+            items = complement;
+            chunks = (chunks - 1).max(2);
+            continue;
+        }
+
+        if chunks >= items.len() {
+            break;
+        }
+        chunks = (chunks * 2).min(items.len());
+    }
+    items
+}
+
+/// answers of every strategy of `puzzle` on `input`, or `None` when it does not parse or a
+/// strategy panics on it.
+pub fn crosscheck(puzzle: &mut dyn DynPuzzle, input: &str) -> Option<Vec<Crosscheck>> {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let parsed = puzzle.parse_str(input).ok()?;
+        strategy::crosscheck(puzzle, parsed.as_ref()).ok()
+    }))
+    .ok()
+    .flatten()
+}
+
+/// the answers `goal` compares on `input`, or `None` when it does not parse or the day panics on
+/// it. Failing to solve the part counts as neither answer when a specific one is expected, as
+/// that is a different problem.
+pub fn answers(puzzle: &mut dyn DynPuzzle, input: &str, goal: &Goal) -> Option<Vec<Crosscheck>> {
+    let (part, expected) = match goal {
+        Goal::Disagree(_) => return crosscheck(puzzle, input),
+        Goal::Expected(part, expected) => (*part, expected),
+    };
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let parsed = puzzle.parse_str(input).ok()?;
+        let mut context = SolveContext::default();
+        let answer = puzzle.solve(part, parsed.as_ref(), &mut context).ok()?;
+        Some(vec![Crosscheck {
+            day: puzzle.day(),
+            part,
+            answers: vec![("expected", expected.clone()), ("answer", answer)],
+        }])
+    }))
+    .ok()
+    .flatten()
+}
+
+/// whether the answers disagree on `part` of `input`, or on either part when `None`.
+pub fn disagrees(checks: &[Crosscheck], part: Option<usize>) -> bool {
+    checks
+        .iter()
+        .filter(|check| part.is_none_or(|part| part == check.part))
+        .any(|check| !check.agrees())
+}
+
+/// whether `input` still meets `goal`.
+fn reproduces(puzzle: &mut dyn DynPuzzle, input: &str, goal: &Goal) -> bool {
+    let part = match goal {
+        Goal::Disagree(part) => *part,
+        Goal::Expected(part, _) => Some(*part),
+    };
+    answers(puzzle, input, goal).is_some_and(|checks| disagrees(&checks, part))
+}
+
+/// shrinks `input` record by record and then line by line for as long as it meets `goal`.
+/// Records are the blank line separated groups some days use. What is left is written back in
+/// the canonical form of the day when that still reproduces.
+pub fn minimize(puzzle: &mut dyn DynPuzzle, input: &str, goal: &Goal) -> Result<Minimized> {
+    if !reproduces(puzzle, input, goal) {
+        return Err(match goal {
+            Goal::Disagree(_) => {
+                "the strategies agree on the input, so there is nothing to minimize".into()
+            }
+            Goal::Expected(part, expected) => format!(
+                "part {} does not give an answer other than {} on the input, so there is \
+                 nothing to minimize",
+                part, expected
+            )
+            .into(),
+        });
+    }
+
+    let mut input = input.trim_end_matches('\n').to_string();
+    loop {
+        let before = input.len();
+        for separator in ["\n\n", "\n"] {
+            let units: Vec<&str> = input.split(separator).collect();
+            let units = ddmin(&units, |units| {
+                reproduces(puzzle, &(units.join(separator) + "\n"), goal)
+            });
+            input = units.join(separator);
+        }
+        if input.len() == before {
+            break;
+        }
+    }
+
+    let mut input = input + "\n";
+    let canonical = puzzle
+        .parse_str(&input)
+        .and_then(|parsed| puzzle.write_input(parsed.as_ref()));
+    if let Ok(canonical) = canonical {
+        if reproduces(puzzle, &canonical, goal) {
+            input = canonical;
+        }
+    }
+    let checks = answers(puzzle, &input, goal).ok_or("the minimized input stopped reproducing")?;
+    Ok(Minimized { input, checks })
+}
+
+/// minimizes `input` as `minimize` does, and turns what is left into an example case called
+/// `name`, with the `params` the puzzle was configured with. The example expects the answers of
+/// the `reference` strategy, the default one when `None`, or the answer the goal expects.
+pub fn minimize_example(
+    puzzle: &mut dyn DynPuzzle,
+    input: &str,
+    goal: &Goal,
+    reference: Option<&str>,
+    name: String,
+    params: Vec<Assignment>,
+) -> Result<(Minimized, Example)> {
+    let reference = match goal {
+        Goal::Expected(_, _) => "expected",
+        Goal::Disagree(_) => {
+            let strategies = puzzle.strategies();
+            if strategies.len() < 2 {
+                return Err(format!("day {} has a single strategy", puzzle.day()).into());
+            }
+            match reference {
+                Some(name) => {
+                    strategies
+                        .iter()
+                        .find(|strategy| strategy.name == name)
+                        .ok_or_else(|| strategy::unknown_strategy(puzzle.day(), name))?
+                        .name
+                }
+                None => strategies[0].name,
+            }
+        }
+    };
+
+    let minimized = minimize(puzzle, input, goal)?;
+    let mut example = Example {
+        day: puzzle.day(),
+        name,
+        params,
+        expected: [None, None],
+        input: minimized.input.clone(),
+    };
+    for check in &minimized.checks {
+        example.expected[check.part - 1] = check
+            .answers
+            .iter()
+            .find(|(name, _)| *name == reference)
+            .map(|(_, answer)| answer.clone())
+            .filter(|answer| !answer.starts_with("error: "));
+    }
+    Ok((minimized, example))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ddmin, minimize, minimize_example, Goal};
+    use crate::{registry::puzzle, strategy::Crosscheck};
+
+    #[test]
+    fn test_ddmin() {
+        let items: Vec<usize> = (0..40).collect();
+        let mut tests = 0;
+        let minimal = ddmin(&items, |items| {
+            tests += 1;
+            items.contains(&3) && items.contains(&31)
+        });
+        assert_eq!(vec![3, 31], minimal);
+        assert!(tests < 100);
+
+        assert_eq!(vec![5], ddmin(&[5], |_| true));
+        assert_eq!(vec![1], ddmin(&[1, 2, 3], |_| true));
+    }
+
+    #[test]
+    fn test_minimize_agreeing() {
+        let mut day1 = puzzle(1).unwrap();
+        let input = "1721\n979\n366\n299\n675\n1456\n";
+        assert!(minimize(day1.as_mut(), input, &Goal::Disagree(None)).is_err());
+        let goal = Goal::Expected(1, "514579".into());
+        assert!(minimize(day1.as_mut(), input, &goal).is_err());
+    }
+
+    #[test]
+    fn test_minimize_example() {
+        let mut day1 = puzzle(1).unwrap();
+        let input = "1721\n979\n366\n299\n675\n1456\n";
+        let goal = Goal::Expected(1, "241861950".into());
+        let (_, example) =
+            minimize_example(day1.as_mut(), input, &goal, None, "wrong".into(), vec![]).unwrap();
+        assert_eq!([Some("241861950".to_string()), None], example.expected);
+        assert_eq!(("wrong", "1721\n299\n"), (&*example.name, &*example.input));
+
+        let mut day2 = puzzle(2).unwrap();
+        let goal = Goal::Disagree(None);
+        let err = minimize_example(day2.as_mut(), "", &goal, None, "x".into(), vec![]);
+        assert_eq!("day 2 has a single strategy", err.unwrap_err().to_string());
+        let mut day7 = puzzle(7).unwrap();
+        let reference = Some("fastest");
+        assert!(minimize_example(day7.as_mut(), "", &goal, reference, "x".into(), vec![]).is_err());
+    }
+
+    #[test]
+    fn test_minimize_expected() {
+        let mut day1 = puzzle(1).unwrap();
+        let input = "1721\n979\n366\n299\n675\n1456\n";
+        let goal = Goal::Expected(1, "241861950".into());
+        let minimized = minimize(day1.as_mut(), input, &goal).unwrap();
+        assert_eq!("1721\n299\n", minimized.input);
+        assert_eq!(
+            vec![Crosscheck {
+                day: 1,
+                part: 1,
+                answers: vec![
+                    ("expected", "241861950".into()),
+                    ("answer", "514579".into())
+                ],
+            }],
+            minimized.checks
+        );
+    }
+}
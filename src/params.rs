@@ -0,0 +1,188 @@
+use std::{fmt, str::FromStr};
+
+use crate::{io::read_lines, registry::DynPuzzle, result::Result};
+
+/// a tunable puzzle parameter, as declared by a day along with its current value.
+#[derive(Debug, PartialEq)]
+pub struct Param {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub value: String,
+}
+
+impl Param {
+    pub fn new<V: fmt::Display>(name: &'static str, description: &'static str, value: V) -> Self {
+        Self {
+            name,
+            description,
+            value: value.to_string(),
+        }
+    }
+}
+
+/// a `name=value` parameter assignment, optionally scoped to a day with `dayN.name=value`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub day: Option<usize>,
+    pub name: String,
+    pub value: String,
+}
+
+impl FromStr for Assignment {
+    type Err = crate::result::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("invalid parameter `{}`, expected `name=value`", s))?;
+        let key = key.trim();
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+
+        let (day, name) = match key.split_once('.') {
+            Some((day, name)) => {
+                let day = day
+                    .strip_prefix("day")
+                    .and_then(|d| d.parse().ok())
+                    .ok_or_else(|| format!("invalid parameter scope `{}`, expected `dayN`", day))?;
+                (Some(day), name)
+            }
+            None => (None, key),
+        };
+        if name.is_empty() {
+            return Err(format!("invalid parameter `{}`, expected `name=value`", s).into());
+        }
+
+        Ok(Self {
+            day,
+            name: name.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+/// reads assignments from a config file with one `name=value` per line. Blank lines and lines
+/// starting with `#` are ignored.
+pub fn read_config(filename: &str) -> Result<Vec<Assignment>> {
+    read_lines(filename)?
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::parse)
+        .collect()
+}
+
+/// applies every assignment to the puzzles it targets. Unscoped assignments go to every puzzle
+/// declaring a parameter with that name, and it is an error for an assignment to match none.
+pub fn apply(puzzles: &mut [Box<dyn DynPuzzle>], assignments: &[Assignment]) -> Result<()> {
+    for assignment in assignments {
+        let mut applied = false;
+        for puzzle in puzzles.iter_mut() {
+            let in_scope = assignment.day.is_none_or(|day| day == puzzle.day());
+            let declared = puzzle.params().iter().any(|p| p.name == assignment.name);
+            if in_scope && (declared || assignment.day.is_some()) {
+                puzzle.set_param(&assignment.name, &assignment.value)?;
+                applied = true;
+            }
+        }
+
+        if !applied {
+            return Err(format!(
+                "no selected day has a parameter named `{}`",
+                assignment.name
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// applies the assignments from the `config` file, if any, followed by the ones in `params`.
+/// Config assignments that no selected puzzle declares are skipped, so one file can hold the
+/// parameters for every day.
+pub fn configure(
+    puzzles: &mut [Box<dyn DynPuzzle>],
+    config: Option<&str>,
+    params: &[Assignment],
+) -> Result<()> {
+    if let Some(config) = config {
+        let assignments: Vec<Assignment> = read_config(config)?
+            .into_iter()
+            .filter(|a| {
+                puzzles.iter().any(|p| {
+                    a.day.is_none_or(|day| day == p.day())
+                        && p.params().iter().any(|param| param.name == a.name)
+                })
+            })
+            .collect();
+        apply(puzzles, &assignments)?;
+    }
+    apply(puzzles, params)
+}
+
+/// parses a parameter value, describing what was `expected` when it is invalid.
+pub fn parse_value<T: FromStr>(name: &str, value: &str, expected: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| invalid_value(name, value, expected))
+}
+
+pub fn invalid_value(name: &str, value: &str, expected: &str) -> crate::result::Error {
+    format!(
+        "invalid value `{}` for parameter `{}`, expected {}",
+        value, name, expected
+    )
+    .into()
+}
+
+pub fn unknown_param(day: usize, name: &str) -> crate::result::Error {
+    format!("day {} has no parameter named `{}`", day, name).into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{apply, Assignment};
+    use crate::registry::puzzles;
+
+    #[test]
+    fn test_parse_assignment() {
+        assert_eq!(
+            Assignment {
+                day: None,
+                name: "target".into(),
+                value: "2020".into(),
+            },
+            "target=2020".parse().unwrap()
+        );
+        assert_eq!(
+            Assignment {
+                day: Some(7),
+                name: "color".into(),
+                value: "dark red".into(),
+            },
+            "day7.color = \"dark red\"".parse().unwrap()
+        );
+        assert!("target".parse::<Assignment>().is_err());
+        assert!("=2020".parse::<Assignment>().is_err());
+        assert!("seven.color=red".parse::<Assignment>().is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut puzzles = puzzles();
+        let assignments = vec!["preamble=5".parse().unwrap()];
+        apply(&mut puzzles, &assignments).unwrap();
+        let day9 = puzzles.iter().find(|p| p.day() == 9).unwrap();
+        assert_eq!("5", day9.params()[0].value);
+
+        let assignments = vec!["preamble=0".parse().unwrap()];
+        assert!(apply(&mut puzzles, &assignments).is_err());
+        let assignments = vec!["day1.preamble=5".parse().unwrap()];
+        assert!(apply(&mut puzzles, &assignments).is_err());
+        let assignments = vec!["nope=5".parse().unwrap()];
+        assert!(apply(&mut puzzles, &assignments).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+/// applies `f` to every item on up to `jobs` threads. Results are handed to `emit` in the order
+/// of `items`, each one as soon as it and every result before it are done. An item `f` panics
+/// on is handed over with the panic message instead, and the other items still run.
+pub fn for_each_ordered<T, R, F, E>(items: &[T], jobs: usize, f: F, mut emit: E)
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    E: FnMut(&T, std::result::Result<R, String>),
+{
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            let sender = sender.clone();
+            let (next, f) = (&next, &f);
+            scope.spawn(move || loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let item = match items.get(idx) {
+                    Some(item) => item,
+                    None => break,
+                };
+                let result = panic::catch_unwind(AssertUnwindSafe(|| f(item)))
+                    .map_err(|p| panic_message(&*p));
+                if sender.send((idx, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+        for (idx, result) in receiver {
+            pending.insert(idx, result);
+            while let Some(result) = pending.remove(&expected) {
+                emit(&items[expected], result);
+                expected += 1;
+            }
+        }
+    });
+}
+
+/// the message a panic was raised with, as far as it can be told from its payload.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panicked without a message".into())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread, time::Duration};
+
+    use super::for_each_ordered;
+
+    #[test]
+    fn test_for_each_ordered() {
+        let items: Vec<u64> = (0..20).collect();
+        for jobs in &[1, 3, 50] {
+            let mut results = vec![];
+            for_each_ordered(
+                &items,
+                *jobs,
+                |n| {
+                    // later items finish first, so results arrive out of order
+                    thread::sleep(Duration::from_millis(20 - n));
+                    n * 2
+                },
+                |_, n| results.push(n.unwrap()),
+            );
+            assert_eq!(items.iter().map(|n| n * 2).collect::<Vec<_>>(), results);
+        }
+    }
+
+    #[test]
+    fn test_for_each_ordered_panic() {
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(|_| {}));
+        let mut results = vec![];
+        for_each_ordered(
+            &[1, 0, 2],
+            2,
+            |n| 4 / n,
+            |n, result| results.push((*n, result)),
+        );
+        std::panic::set_hook(hook);
+        assert_eq!(
+            vec![
+                (1, Ok(4)),
+                (0, Err("attempt to divide by zero".to_string())),
+                (2, Ok(2))
+            ],
+            results
+        );
+    }
+
+    #[test]
+    fn test_for_each_ordered_empty() {
+        let mut called = false;
+        for_each_ordered(&[] as &[usize], 4, |n| *n, |_, _| called = true);
+        assert!(!called);
+    }
+}
@@ -0,0 +1,285 @@
+use std::any::Any;
+
+use crate::{
+    day1, day2, day3, day4, day5, day6, day7, day8, day9,
+    gen::{Generated, Rng},
+    params::{self, Assignment, Param},
+    repl::{Query, QueryState},
+    result::Result,
+    strategy::Strategy,
+    PuzzleRunner,
+};
+
+/// Object safe view of a `PuzzleRunner`, so puzzles for different days can be stored together
+/// and dispatched at runtime.
+pub trait DynPuzzle: Send + Sync {
+    fn day(&self) -> usize;
+    /// path of the default input for the day.
+    fn input_path(&self) -> String;
+    /// parses `filename`, or the default input for the day when no file is given. A filename of
+    /// `-` reads the input from stdin.
+    fn parse(&self, filename: Option<&str>) -> Result<Input>;
+    fn parse_str(&self, input: &str) -> Result<Input>;
+    /// solves `part` for an input previously returned by `parse`. `context` has to be used for
+    /// that input only, as it keeps the answer of part one around for part two.
+    fn solve(&self, part: usize, input: &dyn Any, context: &mut SolveContext) -> Result<String>;
+    /// whether part two is solved from the answer of part one, so the parts of the day cannot
+    /// run at the same time.
+    fn needs_first(&self) -> bool;
+    fn params(&self) -> Vec<Param>;
+    fn set_param(&mut self, name: &str, value: &str) -> Result<()>;
+    fn strategies(&self) -> Vec<Strategy>;
+    fn set_strategy(&mut self, name: &str) -> Result<()>;
+    fn generate(&self, rng: &mut Rng, size: usize) -> Result<Generated>;
+    /// writes an input previously returned by `parse` back as canonical puzzle text.
+    fn write_input(&self, input: &dyn Any) -> Result<String>;
+    fn queries(&self) -> Vec<Query>;
+    /// answers a day specific query on an input previously returned by `parse`.
+    fn query(
+        &self,
+        input: &dyn Any,
+        state: &mut QueryState,
+        name: &str,
+        args: &str,
+    ) -> Result<String>;
+}
+
+/// a parsed input of any day, as returned by `DynPuzzle::parse`.
+pub type Input = Box<dyn Any + Send + Sync>;
+
+/// answers computed so far for a single parsed input.
+#[derive(Default)]
+pub struct SolveContext {
+    first: Option<Box<dyn Any>>,
+}
+
+impl<P> DynPuzzle for P
+where
+    P: PuzzleRunner + Send + Sync,
+    P::Input: 'static,
+    P::First: 'static,
+{
+    fn day(&self) -> usize {
+        P::DAY
+    }
+
+    fn input_path(&self) -> String {
+        PuzzleRunner::input_path(self)
+    }
+
+    fn parse(&self, filename: Option<&str>) -> Result<Input> {
+        let input = match filename {
+            Some("-") => self.parse_reader(std::io::stdin().lock())?,
+            Some(filename) => self.parse_input(filename)?,
+            None => self.input()?,
+        };
+        Ok(Box::new(input))
+    }
+
+    fn parse_str(&self, input: &str) -> Result<Input> {
+        Ok(Box::new(PuzzleRunner::parse_str(self, input)?))
+    }
+
+    fn solve(&self, part: usize, input: &dyn Any, context: &mut SolveContext) -> Result<String> {
+        let input = input
+            .downcast_ref::<P::Input>()
+            .ok_or_else(|| format!("input does not belong to day {}", P::DAY))?;
+        match part {
+            1 => {
+                let first = self.part_one(input)?;
+                let answer = first.to_string();
+                context.first = Some(Box::new(first));
+                Ok(answer)
+            }
+            2 => {
+                // part two was asked for on its own, so part one has to be solved first when
+                // part two builds on it
+                if P::NEEDS_FIRST && context.first.is_none() {
+                    context.first = Some(Box::new(self.part_one(input)?));
+                }
+                let first = match &context.first {
+                    Some(first) => Some(first.downcast_ref::<P::First>().ok_or_else(|| {
+                        format!("part one answer does not belong to day {}", P::DAY)
+                    })?),
+                    None => None,
+                };
+                Ok(self.part_two(input, first)?.to_string())
+            }
+            _ => Err(format!("invalid part `{}`, expected 1 or 2", part).into()),
+        }
+    }
+
+    fn needs_first(&self) -> bool {
+        P::NEEDS_FIRST
+    }
+
+    fn params(&self) -> Vec<Param> {
+        PuzzleRunner::params(self)
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<()> {
+        PuzzleRunner::set_param(self, name, value)
+    }
+
+    fn strategies(&self) -> Vec<Strategy> {
+        PuzzleRunner::strategies(self)
+    }
+
+    fn set_strategy(&mut self, name: &str) -> Result<()> {
+        PuzzleRunner::set_strategy(self, name)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Result<Generated> {
+        PuzzleRunner::generate(self, rng, size)
+    }
+
+    fn write_input(&self, input: &dyn Any) -> Result<String> {
+        let input = input
+            .downcast_ref::<P::Input>()
+            .ok_or_else(|| format!("input does not belong to day {}", P::DAY))?;
+        PuzzleRunner::write_input(self, input)
+    }
+
+    fn queries(&self) -> Vec<Query> {
+        PuzzleRunner::queries(self)
+    }
+
+    fn query(
+        &self,
+        input: &dyn Any,
+        state: &mut QueryState,
+        name: &str,
+        args: &str,
+    ) -> Result<String> {
+        let input = input
+            .downcast_ref::<P::Input>()
+            .ok_or_else(|| format!("input does not belong to day {}", P::DAY))?;
+        PuzzleRunner::query(self, input, state, name, args)
+    }
+}
+
+/// every implemented puzzle, in day order.
+pub fn puzzles() -> Vec<Box<dyn DynPuzzle>> {
+    vec![
+        Box::new(day1::Puzzle::default()),
+        Box::new(day2::Puzzle),
+        Box::new(day3::Puzzle::default()),
+        Box::new(day4::Puzzle),
+        Box::new(day5::Puzzle),
+        Box::new(day6::Puzzle),
+        Box::new(day7::Puzzle::default()),
+        Box::new(day8::Puzzle),
+        Box::new(day9::Puzzle::default()),
+    ]
+}
+
+pub fn puzzle(day: usize) -> Result<Box<dyn DynPuzzle>> {
+    puzzles()
+        .into_iter()
+        .find(|p| p.day() == day)
+        .ok_or_else(|| format!("day {} is not implemented", day).into())
+}
+
+/// the puzzle for `day`, or every puzzle when no day is given.
+pub fn selected(day: Option<usize>) -> Result<Vec<Box<dyn DynPuzzle>>> {
+    match day {
+        Some(day) => Ok(vec![puzzle(day)?]),
+        None => Ok(puzzles()),
+    }
+}
+
+/// the selected puzzles with their parameters set from `config` and then `assignments`, and
+/// switched over to `strategy` when one is given.
+pub fn configured(
+    day: Option<usize>,
+    config: Option<&str>,
+    assignments: &[Assignment],
+    strategy: Option<&str>,
+) -> Result<Vec<Box<dyn DynPuzzle>>> {
+    let mut puzzles = selected(day)?;
+    params::configure(&mut puzzles, config, assignments)?;
+    if let Some(strategy) = strategy {
+        for puzzle in puzzles.iter_mut() {
+            puzzle.set_strategy(strategy)?;
+        }
+    }
+    Ok(puzzles)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{configured, puzzle, puzzles, selected, SolveContext};
+
+    #[test]
+    fn test_puzzles_in_day_order() {
+        let days: Vec<usize> = puzzles().iter().map(|p| p.day()).collect();
+        assert_eq!((1..=days.len()).collect::<Vec<_>>(), days);
+    }
+
+    #[test]
+    fn test_configured() {
+        assert_eq!(puzzles().len(), selected(None).unwrap().len());
+        assert!(selected(Some(26)).is_err());
+
+        let assignments = vec!["color=dark olive".parse().unwrap()];
+        let puzzles = configured(Some(7), None, &assignments, Some("memoized")).unwrap();
+        let input = puzzles[0].parse(Some("./data/day7_test.txt")).unwrap();
+        let mut context = SolveContext::default();
+        assert_eq!(
+            "7",
+            puzzles[0].solve(2, input.as_ref(), &mut context).unwrap()
+        );
+        assert!(configured(Some(7), None, &[], Some("fastest")).is_err());
+    }
+
+    #[test]
+    fn test_solve() {
+        let puzzle = puzzle(7).unwrap();
+        let input = puzzle.parse(Some("./data/day7_test.txt")).unwrap();
+        let mut context = SolveContext::default();
+        assert_eq!("4", puzzle.solve(1, input.as_ref(), &mut context).unwrap());
+        assert_eq!("32", puzzle.solve(2, input.as_ref(), &mut context).unwrap());
+        assert!(puzzle.solve(3, input.as_ref(), &mut context).is_err());
+    }
+
+    #[test]
+    fn test_solve_part_two_alone() {
+        let mut puzzle = puzzle(9).unwrap();
+        puzzle.set_param("preamble", "5").unwrap();
+        let input = puzzle.parse(Some("./data/day9_test.txt")).unwrap();
+        let mut context = SolveContext::default();
+        assert_eq!("62", puzzle.solve(2, input.as_ref(), &mut context).unwrap());
+    }
+
+    #[test]
+    fn test_solve_part_two_without_part_one() {
+        // the entries have a triple summing to the target but no pair, and day 1 does not need
+        // the answer of part one for part two
+        let mut puzzle = puzzle(1).unwrap();
+        puzzle.set_param("target", "7").unwrap();
+        let input = puzzle.parse_str("1\n2\n4\n").unwrap();
+        let mut context = SolveContext::default();
+        assert_eq!("8", puzzle.solve(2, input.as_ref(), &mut context).unwrap());
+        assert!(puzzle.solve(1, input.as_ref(), &mut context).is_err());
+        assert!(!puzzle.needs_first());
+        assert!(super::puzzle(9).unwrap().needs_first());
+    }
+
+    #[test]
+    fn test_parse_str() {
+        let puzzle = puzzle(8).unwrap();
+        let input = puzzle.parse_str("nop +0\nacc +1\njmp -2\n").unwrap();
+        let mut context = SolveContext::default();
+        assert_eq!("1", puzzle.solve(1, input.as_ref(), &mut context).unwrap());
+    }
+
+    #[test]
+    fn test_solve_wrong_input() {
+        let input = puzzle(1).unwrap().parse(Some("./data/day1.txt")).unwrap();
+        let mut context = SolveContext::default();
+        assert!(puzzle(2)
+            .unwrap()
+            .solve(1, input.as_ref(), &mut context)
+            .is_err());
+    }
+}
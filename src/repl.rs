@@ -0,0 +1,272 @@
+use std::{
+    any::Any,
+    io::{BufRead, Write},
+};
+
+use crate::{
+    bench::time,
+    io::render_error,
+    params::Assignment,
+    registry::{DynPuzzle, Input, SolveContext},
+    result::{Error, Result},
+};
+
+/// a day specific command offered by the REPL, as declared by the day.
+#[derive(Debug, PartialEq)]
+pub struct Query {
+    pub name: &'static str,
+    /// arguments the query takes, like `<color>`
+    pub usage: &'static str,
+    pub description: &'static str,
+}
+
+impl Query {
+    pub fn new(name: &'static str, usage: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            usage,
+            description,
+        }
+    }
+}
+
+/// state a day keeps between queries, like the position of a program being stepped through. It
+/// is cleared whenever the input is reloaded.
+#[derive(Default)]
+pub struct QueryState {
+    state: Option<Box<dyn Any + Send>>,
+}
+
+impl QueryState {
+    /// the state of type `T`, starting from its default the first time it is asked for.
+    pub fn get<T: Any + Send + Default>(&mut self) -> &mut T {
+        if !self.state.as_ref().is_some_and(|state| state.is::<T>()) {
+            self.state = Some(Box::new(T::default()));
+        }
+        // the state was just made sure to hold a `T`
+        self.state.as_mut().unwrap().downcast_mut().unwrap()
+    }
+
+    pub fn clear(&mut self) {
+        self.state = None;
+    }
+}
+
+pub fn unknown_query(day: usize, name: &str) -> Error {
+    format!(
+        "unknown command `{}`, day {} has no such query; try `help`",
+        name, day
+    )
+    .into()
+}
+
+const HELP: &str = "commands:
+    part1, part2           solve a part of the puzzle
+    time COMMAND           run a command and report how long it took
+    reload                 parse the input again
+    params                 list the parameters of the day
+    param NAME=VALUE       set a parameter
+    strategy [NAME]        list the strategies of the day, or switch to one
+    help                   show this message
+    quit                   leave the repl";
+
+/// an interactive session on the parsed input of a single day.
+pub struct Repl {
+    puzzle: Box<dyn DynPuzzle>,
+    /// input file to use instead of the default one for the day
+    filename: Option<String>,
+    input: Input,
+    /// answers of the parts solved so far, until the input, a parameter or the strategy changes
+    context: SolveContext,
+    state: QueryState,
+}
+
+impl Repl {
+    /// parses the input of `puzzle`, from `filename` when given.
+    pub fn new(puzzle: Box<dyn DynPuzzle>, filename: Option<String>) -> Result<Self> {
+        if filename.as_deref() == Some("-") {
+            return Err("the repl reads commands from stdin, so its input has to be a file".into());
+        }
+        let input = puzzle.parse(filename.as_deref())?;
+        Ok(Self {
+            puzzle,
+            filename,
+            input,
+            context: SolveContext::default(),
+            state: QueryState::default(),
+        })
+    }
+
+    pub fn filename(&self) -> String {
+        self.filename
+            .clone()
+            .unwrap_or_else(|| self.puzzle.input_path())
+    }
+
+    /// runs a single command line, returning what it printed.
+    pub fn execute(&mut self, line: &str) -> Result<String> {
+        let line = line.trim();
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        let args = args.trim();
+        match command {
+            "" => Ok(String::new()),
+            "help" => {
+                let mut help = HELP.to_string();
+                let queries = self.puzzle.queries();
+                if !queries.is_empty() {
+                    help += &format!("\nday {} queries:", self.puzzle.day());
+                }
+                for query in queries {
+                    let usage = format!("{} {}", query.name, query.usage);
+                    help += &format!("\n    {:<22} {}", usage.trim(), query.description);
+                }
+                Ok(help)
+            }
+            "part1" | "part2" => {
+                let part = if command == "part1" { 1 } else { 2 };
+                self.puzzle
+                    .solve(part, self.input.as_ref(), &mut self.context)
+            }
+            "time" if args.is_empty() => Err("expected a command to time".into()),
+            "time" => {
+                let (output, elapsed) = time(|| self.execute(args))?;
+                Ok(format!("{}\n(took {:?})", output, elapsed).trim().into())
+            }
+            "reload" => {
+                self.input = self.puzzle.parse(self.filename.as_deref())?;
+                self.context = SolveContext::default();
+                self.state.clear();
+                Ok(format!("reloaded {}", self.filename()))
+            }
+            "params" => Ok(self
+                .puzzle
+                .params()
+                .iter()
+                .map(|param| format!("{} = {}", param.name, param.value))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            "param" => {
+                let assignment: Assignment = args.parse()?;
+                if let Some(day) = assignment.day.filter(|day| *day != self.puzzle.day()) {
+                    return Err(format!(
+                        "the repl is on day {}, not day {}",
+                        self.puzzle.day(),
+                        day
+                    )
+                    .into());
+                }
+                self.puzzle.set_param(&assignment.name, &assignment.value)?;
+                self.context = SolveContext::default();
+                Ok(format!("{} = {}", assignment.name, assignment.value))
+            }
+            "strategy" if args.is_empty() => Ok(self
+                .puzzle
+                .strategies()
+                .iter()
+                .map(|strategy| format!("{:<22} {}", strategy.name, strategy.description))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            "strategy" => {
+                self.puzzle.set_strategy(args)?;
+                self.context = SolveContext::default();
+                Ok(format!("strategy {}", args))
+            }
+            name => self
+                .puzzle
+                .query(self.input.as_ref(), &mut self.state, name, args),
+        }
+    }
+
+    /// reads commands from `input` until it ends or `quit` is given, writing a prompt before each
+    /// one and the outcome after it.
+    pub fn run(&mut self, mut input: impl BufRead, mut output: impl Write) -> Result<()> {
+        loop {
+            write!(output, "day{}> ", self.puzzle.day())?;
+            output.flush()?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                writeln!(output)?;
+                return Ok(());
+            }
+
+            match line.trim() {
+                "quit" | "exit" => return Ok(()),
+                line => match self.execute(line) {
+                    Ok(out) if out.is_empty() => {}
+                    Ok(out) => writeln!(output, "{}", out)?,
+                    Err(err) => writeln!(output, "{}", render_error(&err, &self.filename()))?,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{QueryState, Repl};
+    use crate::registry::puzzle;
+
+    fn repl(day: usize) -> Repl {
+        let filename = format!("./data/day{}_test.txt", day);
+        Repl::new(puzzle(day).unwrap(), Some(filename)).unwrap()
+    }
+
+    #[test]
+    fn test_execute() {
+        let mut repl = repl(9);
+        assert_eq!("preamble = 5", repl.execute("param preamble=5").unwrap());
+        assert_eq!("127", repl.execute("part1").unwrap());
+        assert!(repl
+            .execute("time part2")
+            .unwrap()
+            .starts_with("62\n(took "));
+        assert!(repl.execute("reload").is_ok());
+        assert!(repl.execute("param day1.target=5").is_err());
+        assert!(repl.execute("strategy").unwrap().is_empty());
+        assert!(repl.execute("strategy fastest").is_err());
+        assert!(repl.execute("frobnicate").is_err());
+    }
+
+    #[test]
+    fn test_answers_are_kept() {
+        let mut day9 = repl(9);
+        day9.execute("param preamble=5").unwrap();
+        assert_eq!("127", day9.execute("part1").unwrap());
+        assert_eq!("62", day9.execute("part2").unwrap());
+        // with a longer preamble there is no invalid number, so no range to look for either
+        day9.execute("param preamble=25").unwrap();
+        assert!(day9.execute("part2").is_err());
+        day9.execute("param preamble=5").unwrap();
+        assert_eq!("62", day9.execute("part2").unwrap());
+
+        let mut day7 = repl(7);
+        assert!(day7.execute("strategy").unwrap().contains("memoized"));
+        assert_eq!(
+            "strategy memoized",
+            day7.execute("strategy memoized").unwrap()
+        );
+        assert_eq!("32", day7.execute("part2").unwrap());
+    }
+
+    #[test]
+    fn test_run() {
+        let mut repl = repl(7);
+        let mut output = vec![];
+        let commands = "part1\ncontains \"shiny gold\"\nfrobnicate\nquit\npart2\n";
+        repl.run(commands.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("day7> 4\nday7> 4 bags can contain shiny gold"));
+        assert!(output.contains("error: unknown command `frobnicate`"));
+        assert!(!output.contains("32"));
+    }
+
+    #[test]
+    fn test_query_state() {
+        let mut state = QueryState::default();
+        *state.get::<usize>() += 2;
+        assert_eq!(2, *state.get::<usize>());
+        assert_eq!("", state.get::<String>());
+        state.clear();
+        assert_eq!(0, *state.get::<usize>());
+    }
+}
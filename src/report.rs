@@ -0,0 +1,315 @@
+use std::time::Duration;
+
+/// formats checked cases can be rendered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Report {
+    #[default]
+    Text,
+    /// JUnit XML, one test suite per day
+    Junit,
+    /// Test Anything Protocol
+    Tap,
+}
+
+/// outcome of checking one day and part against its expected answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Passed,
+    /// the answer did not match the expected one
+    Failed(String),
+    /// the part could not be parsed or solved
+    Error(String),
+    /// there was nothing to check the answer against
+    Skipped(String),
+}
+
+/// one day and part reported as a test case.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    /// group the case belongs to, like `day4`
+    pub suite: String,
+    /// name of the case within its suite, like `part 1`
+    pub name: String,
+    pub answer: Option<String>,
+    pub expected: Option<String>,
+    pub duration: Duration,
+    pub status: Status,
+}
+
+impl Case {
+    /// a case for `answer`, passed or failed depending on whether it matches `expected`.
+    pub fn checked(
+        suite: String,
+        name: String,
+        answer: String,
+        expected: Option<&str>,
+        duration: Duration,
+    ) -> Self {
+        let status = match expected {
+            Some(expected) if expected == answer => Status::Passed,
+            Some(expected) => Status::Failed(format!("expected {}, got {}", expected, answer)),
+            None => Status::Skipped(format!("no expected answer, got {}", answer)),
+        };
+        Case {
+            suite,
+            name,
+            answer: Some(answer),
+            expected: expected.map(String::from),
+            duration,
+            status,
+        }
+    }
+
+    /// a case for a part that failed with `message` before giving an answer.
+    pub fn error(suite: String, name: String, expected: Option<&str>, message: String) -> Self {
+        Case {
+            suite,
+            name,
+            answer: None,
+            expected: expected.map(String::from),
+            duration: Duration::default(),
+            status: Status::Error(message),
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        matches!(self.status, Status::Passed | Status::Skipped(_))
+    }
+}
+
+/// renders `cases` in the `report` format, with `name` naming the whole run where the format
+/// has a place for it.
+pub fn render(name: &str, cases: &[Case], report: Report) -> String {
+    match report {
+        Report::Text => cases
+            .iter()
+            .map(|case| {
+                let outcome = match &case.status {
+                    Status::Passed => "ok".to_string(),
+                    Status::Failed(message) => format!("MISMATCH {}", message),
+                    Status::Error(message) => format!("FAILED {}", message),
+                    Status::Skipped(message) => message.clone(),
+                };
+                format!("{} {}: {}", case.suite, case.name, outcome)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Report::Junit => junit(name, cases),
+        Report::Tap => tap(cases),
+    }
+}
+
+/// renders `cases` as a JUnit XML document, with one test suite per distinct `suite`.
+pub fn junit(name: &str, cases: &[Case]) -> String {
+    let count =
+        |f: fn(&Status) -> bool, cases: &[&Case]| cases.iter().filter(|c| f(&c.status)).count();
+    let failed = |s: &Status| matches!(s, Status::Failed(_));
+    let errored = |s: &Status| matches!(s, Status::Error(_));
+    let skipped = |s: &Status| matches!(s, Status::Skipped(_));
+    let seconds = |cases: &[&Case]| {
+        cases
+            .iter()
+            .map(|c| c.duration)
+            .sum::<Duration>()
+            .as_secs_f64()
+    };
+
+    let all: Vec<&Case> = cases.iter().collect();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml += &format!(
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.6}\">\n",
+        escape_xml(name),
+        all.len(),
+        count(failed, &all),
+        count(errored, &all),
+        seconds(&all)
+    );
+
+    let mut suites: Vec<&str> = vec![];
+    for case in cases {
+        if !suites.contains(&case.suite.as_str()) {
+            suites.push(&case.suite);
+        }
+    }
+    for suite in suites {
+        let cases: Vec<&Case> = cases.iter().filter(|c| c.suite == suite).collect();
+        xml += &format!("  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.6}\">\n",
+            escape_xml(suite),
+            cases.len(),
+            count(failed, &cases),
+            count(errored, &cases),
+            count(skipped, &cases),
+            seconds(&cases)
+        );
+        for case in cases {
+            xml += &format!(
+                "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.6}\">\n",
+                escape_xml(&case.suite),
+                escape_xml(&case.name),
+                case.duration.as_secs_f64()
+            );
+            match &case.status {
+                Status::Passed => {}
+                Status::Failed(message) => {
+                    xml += &format!("      <failure message=\"{}\"/>\n", escape_xml(message));
+                }
+                Status::Error(message) => {
+                    xml += &format!("      <error message=\"{}\"/>\n", escape_xml(message));
+                }
+                Status::Skipped(message) => {
+                    xml += &format!("      <skipped message=\"{}\"/>\n", escape_xml(message));
+                }
+            }
+            xml += &format!(
+                "      <system-out>answer: {}\nexpected: {}</system-out>\n    </testcase>\n",
+                escape_xml(case.answer.as_deref().unwrap_or("-")),
+                escape_xml(case.expected.as_deref().unwrap_or("-"))
+            );
+        }
+        xml += "  </testsuite>\n";
+    }
+    xml += "</testsuites>";
+    xml
+}
+
+/// renders `cases` in the Test Anything Protocol, with the answers in a YAML block per case.
+pub fn tap(cases: &[Case]) -> String {
+    let mut tap = format!("TAP version 13\n1..{}", cases.len());
+    for (idx, case) in cases.iter().enumerate() {
+        let description = format!("{} {}", case.suite, case.name);
+        tap += &match &case.status {
+            Status::Passed => format!("\nok {} - {}", idx + 1, description),
+            Status::Skipped(message) => {
+                format!("\nok {} - {} # SKIP {}", idx + 1, description, message)
+            }
+            Status::Failed(_) | Status::Error(_) => {
+                format!("\nnot ok {} - {}", idx + 1, description)
+            }
+        };
+
+        tap += "\n  ---";
+        if let Status::Failed(message) | Status::Error(message) = &case.status {
+            tap += &format!("\n  message: {:?}", message);
+        }
+        if let Some(answer) = &case.answer {
+            tap += &format!("\n  answer: {:?}", answer);
+        }
+        if let Some(expected) = &case.expected {
+            tap += &format!("\n  expected: {:?}", expected);
+        }
+        tap += &format!(
+            "\n  duration_ms: {:.3}\n  ...",
+            case.duration.as_secs_f64() * 1000.0
+        );
+    }
+    tap
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            '\'' => escaped += "&apos;",
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{junit, render, tap, Case, Report};
+
+    fn cases() -> Vec<Case> {
+        let case = |name: &str, answer: &str, expected: Option<&str>| {
+            Case::checked(
+                "day9".into(),
+                name.into(),
+                answer.into(),
+                expected,
+                Duration::from_micros(1500),
+            )
+        };
+        vec![
+            case("part 1", "127", Some("127")),
+            case("part 2", "61", Some("62")),
+            Case::error(
+                "day8".into(),
+                "part 1".into(),
+                None,
+                "no solution: <loop>".into(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_junit() {
+        assert_eq!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="verify" tests="3" failures="1" errors="1" time="0.003000">
+  <testsuite name="day9" tests="2" failures="1" errors="0" skipped="0" time="0.003000">
+    <testcase classname="day9" name="part 1" time="0.001500">
+      <system-out>answer: 127
+expected: 127</system-out>
+    </testcase>
+    <testcase classname="day9" name="part 2" time="0.001500">
+      <failure message="expected 62, got 61"/>
+      <system-out>answer: 61
+expected: 62</system-out>
+    </testcase>
+  </testsuite>
+  <testsuite name="day8" tests="1" failures="0" errors="1" skipped="0" time="0.000000">
+    <testcase classname="day8" name="part 1" time="0.000000">
+      <error message="no solution: &lt;loop&gt;"/>
+      <system-out>answer: -
+expected: -</system-out>
+    </testcase>
+  </testsuite>
+</testsuites>"#,
+            junit("verify", &cases())
+        );
+    }
+
+    #[test]
+    fn test_render_text() {
+        assert_eq!(
+            "day9 part 1: ok\n\
+             day9 part 2: MISMATCH expected 62, got 61\n\
+             day8 part 1: FAILED no solution: <loop>",
+            render("verify", &cases(), Report::Text)
+        );
+    }
+
+    #[test]
+    fn test_tap() {
+        assert_eq!(
+            r#"TAP version 13
+1..3
+ok 1 - day9 part 1
+  ---
+  answer: "127"
+  expected: "127"
+  duration_ms: 1.500
+  ...
+not ok 2 - day9 part 2
+  ---
+  message: "expected 62, got 61"
+  answer: "61"
+  expected: "62"
+  duration_ms: 1.500
+  ...
+not ok 3 - day8 part 1
+  ---
+  message: "no solution: <loop>"
+  duration_ms: 0.000
+  ..."#,
+            tap(&cases())
+        );
+    }
+}
@@ -1 +1,167 @@
-pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    /// malformed puzzle input, `line` and `column` start at 1
+    Parse {
+        day: usize,
+        line: usize,
+        column: usize,
+        /// number of characters the error spans, at least 1
+        len: usize,
+        message: String,
+        hint: Option<String>,
+        /// the offending input line
+        text: String,
+    },
+    /// the input parsed fine but has no answer for the requested part
+    NoSolution(String),
+    Other(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::Parse {
+                day,
+                line,
+                column,
+                message,
+                hint,
+                ..
+            } => {
+                write!(f, "day{}:{}:{}: {}", day, line, column, message)?;
+                if let Some(hint) = hint {
+                    write!(f, ", {}", hint)?;
+                }
+                Ok(())
+            }
+            Error::NoSolution(reason) => write!(f, "no solution: {}", reason),
+            Error::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Other(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Other(message.to_string())
+    }
+}
+
+/// error found while parsing a single line of input, before it is known which day and line
+/// it belongs to.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    /// position of the error within the line, starting at 1
+    pub column: usize,
+    /// number of characters the error spans, at least 1
+    pub len: usize,
+    pub message: String,
+    /// what was expected instead, shown under the offending text when rendered
+    pub hint: Option<String>,
+}
+
+impl ParseError {
+    pub fn new<S: Into<String>>(column: usize, message: S) -> Self {
+        Self {
+            column,
+            len: 1,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    /// error spanning `part`, which must be a slice of `line`.
+    pub fn within<S: Into<String>>(line: &str, part: &str, message: S) -> Self {
+        let offset = part.as_ptr() as usize - line.as_ptr() as usize;
+        Self {
+            len: part.chars().count().max(1),
+            ..Self::new(line[..offset].chars().count() + 1, message)
+        }
+    }
+
+    /// error pointing just past the end of `line`, for input that stops too early.
+    pub fn end_of<S: Into<String>>(line: &str, message: S) -> Self {
+        Self::new(line.chars().count() + 1, message)
+    }
+
+    pub fn with_hint<S: Into<String>>(self, hint: S) -> Self {
+        Self {
+            hint: Some(hint.into()),
+            ..self
+        }
+    }
+
+    /// attaches the day and line number the error was found in, along with the line itself.
+    pub fn at(self, day: usize, line: usize, text: &str) -> Error {
+        Error::Parse {
+            day,
+            line,
+            column: self.column,
+            len: self.len,
+            message: self.message,
+            hint: self.hint,
+            text: text.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.column, self.message)?;
+        if let Some(hint) = &self.hint {
+            write!(f, ", {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ParseError;
+
+    #[test]
+    fn test_parse_error_display() {
+        let line = "light red bags contain x bright white bag.";
+        let err = ParseError::within(line, &line[23..24], "expected bag count").at(7, 12, line);
+        assert_eq!("day7:12:24: expected bag count", err.to_string());
+
+        let err = ParseError::new(1, "unknown opcode `jnz`")
+            .with_hint("expected nop/acc/jmp")
+            .at(8, 2, "jnz +4");
+        assert_eq!(
+            "day8:2:1: unknown opcode `jnz`, expected nop/acc/jmp",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_within() {
+        let line = "1-3 a: abcde";
+        let err = ParseError::within(line, &line[7..], "bad password");
+        assert_eq!((8, 5), (err.column, err.len));
+    }
+
+    #[test]
+    fn test_end_of() {
+        assert_eq!(ParseError::new(4, "eof"), ParseError::end_of("abc", "eof"));
+    }
+}
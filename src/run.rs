@@ -0,0 +1,262 @@
+use std::{fmt, panic, thread, time::Duration};
+
+use crate::{
+    bench::time,
+    io::render_error,
+    json::Value,
+    registry::{DynPuzzle, Input, SolveContext},
+    result::Result,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Text,
+    /// one JSON object per line for each day and part
+    Json,
+}
+
+/// what running a day printed, kept aside so days run in parallel can be printed in day order.
+#[derive(Debug, Default)]
+pub struct Output {
+    pub stdout: String,
+    pub stderr: String,
+    pub failures: usize,
+}
+
+impl Output {
+    /// the output of a day that panicked instead of returning its output.
+    pub fn panicked(day: usize, message: &str) -> Self {
+        let mut output = Output::default();
+        output.err(format!("day {} panicked: {}", day, message));
+        output.failures += 1;
+        output
+    }
+
+    fn out(&mut self, line: impl fmt::Display) {
+        self.stdout += &format!("{}\n", line);
+    }
+
+    fn err(&mut self, line: impl fmt::Display) {
+        self.stderr += &format!("{}\n", line);
+    }
+}
+
+/// runs `part` of `puzzle`, or both parts when `None`, on `input` or the default input of the
+/// day, collecting the answers and how long each step took. Failures are reported and skipped,
+/// and counted in the output.
+pub fn run(
+    puzzle: &dyn DynPuzzle,
+    part: Option<usize>,
+    input: Option<&str>,
+    format: Format,
+) -> Output {
+    let mut output = Output::default();
+    let parts: Vec<usize> = match part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+    let (parsed, parse_time) = match time(|| puzzle.parse(input)) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            match format {
+                Format::Text => {
+                    let filename = match input {
+                        Some("-") => "<stdin>".to_string(),
+                        Some(input) => input.to_string(),
+                        None => puzzle.input_path(),
+                    };
+                    output.err(render_error(&err, &filename));
+                }
+                Format::Json => {
+                    report_failure(&mut output, puzzle.day(), None, &err.to_string(), format)
+                }
+            }
+            output.failures = parts.len();
+            return output;
+        }
+    };
+
+    if format == Format::Text {
+        output.out(format!(
+            "\nday {} solution (parsed in {:?})",
+            puzzle.day(),
+            parse_time
+        ));
+    }
+    for (part, solved) in solve(puzzle, &parts, &parsed) {
+        let (answer, solve_time) = match solved {
+            Ok(solved) => solved,
+            Err(err) => {
+                output.failures += 1;
+                report_failure(
+                    &mut output,
+                    puzzle.day(),
+                    Some(part),
+                    &err.to_string(),
+                    format,
+                );
+                continue;
+            }
+        };
+
+        match format {
+            Format::Text => output.out(format!(
+                "    part {}: {} ({:?})",
+                part_name(part),
+                answer,
+                solve_time
+            )),
+            Format::Json => output.out(
+                Value::object()
+                    .with("day", puzzle.day())
+                    .with("part", part)
+                    .with("answer", answer)
+                    .with("parse_time_ns", parse_time.as_nanos() as i64)
+                    .with("solve_time_ns", solve_time.as_nanos() as i64),
+            ),
+        }
+    }
+    output
+}
+
+/// solves `parts` of `input`, at the same time unless part two is solved from the answer of
+/// part one.
+fn solve(
+    puzzle: &dyn DynPuzzle,
+    parts: &[usize],
+    input: &Input,
+) -> Vec<(usize, Result<(String, Duration)>)> {
+    if puzzle.needs_first() {
+        let mut context = SolveContext::default();
+        return parts
+            .iter()
+            .map(|&part| {
+                let solved = time(|| puzzle.solve(part, input.as_ref(), &mut context));
+                (part, solved)
+            })
+            .collect();
+    }
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = parts
+            .iter()
+            .map(|&part| {
+                let handle = scope.spawn(move || {
+                    let mut context = SolveContext::default();
+                    time(|| puzzle.solve(part, input.as_ref(), &mut context))
+                });
+                (part, handle)
+            })
+            .collect();
+        // a panicking part panics the day, as it would have without the threads
+        handles
+            .into_iter()
+            .map(|(part, handle)| {
+                let solved = handle
+                    .join()
+                    .unwrap_or_else(|payload| panic::resume_unwind(payload));
+                (part, solved)
+            })
+            .collect()
+    })
+}
+
+fn report_failure(
+    output: &mut Output,
+    day: usize,
+    part: Option<usize>,
+    message: &str,
+    format: Format,
+) {
+    match (format, part) {
+        (Format::Text, Some(part)) => output.err(format!(
+            "day {} part {} failed: {}",
+            day,
+            part_name(part),
+            message
+        )),
+        (Format::Text, None) => output.err(format!("day {} failed: {}", day, message)),
+        (Format::Json, part) => output.out(
+            Value::object()
+                .with("day", day)
+                .with("part", part)
+                .with("error", message),
+        ),
+    }
+}
+
+fn part_name(part: usize) -> &'static str {
+    match part {
+        1 => "one",
+        _ => "two",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{run, Format, Output};
+    use crate::registry::puzzle;
+
+    #[test]
+    fn test_run() {
+        let day8 = puzzle(8).unwrap();
+        let output = run(
+            day8.as_ref(),
+            None,
+            Some("./data/day8_test.txt"),
+            Format::Text,
+        );
+        assert_eq!(0, output.failures);
+        assert!(output.stdout.starts_with("\nday 8 solution (parsed in "));
+        assert!(output.stdout.contains("\n    part one: 5 ("));
+        assert!(output.stdout.contains("\n    part two: 8 ("));
+
+        // day 9 solves part two from the answer of part one
+        let mut day9 = puzzle(9).unwrap();
+        day9.set_param("preamble", "5").unwrap();
+        let output = run(
+            day9.as_ref(),
+            Some(2),
+            Some("./data/day9_test.txt"),
+            Format::Json,
+        );
+        assert_eq!(0, output.failures);
+        assert!(output
+            .stdout
+            .starts_with("{\"day\":9,\"part\":2,\"answer\":\"62\","));
+    }
+
+    #[test]
+    fn test_run_failures() {
+        let mut day9 = puzzle(9).unwrap();
+        let output = run(
+            day9.as_ref(),
+            None,
+            Some("./data/day9_test.txt"),
+            Format::Text,
+        );
+        assert_eq!(2, output.failures);
+        assert!(output
+            .stderr
+            .starts_with("day 9 part one failed: no solution"));
+
+        day9.set_param("preamble", "5").unwrap();
+        let output = run(
+            day9.as_ref(),
+            None,
+            Some("./data/day8_test.txt"),
+            Format::Json,
+        );
+        assert_eq!(2, output.failures);
+        assert!(output
+            .stdout
+            .starts_with("{\"day\":9,\"part\":null,\"error\":\"day9:1:1: "));
+
+        let output = Output::panicked(3, "index out of bounds");
+        assert_eq!(
+            (1, "day 3 panicked: index out of bounds\n"),
+            (output.failures, &*output.stderr)
+        );
+    }
+}
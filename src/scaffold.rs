@@ -0,0 +1,203 @@
+use std::{fs, path::Path, process::Command};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::result::Result;
+
+lazy_static! {
+    static ref MOD_RE: Regex = Regex::new(r"(?m)^pub mod day(\d+);\n").unwrap();
+    static ref IMPORT_RE: Regex = Regex::new(r"\bday(\d+),").unwrap();
+    static ref REGISTER_RE: Regex =
+        Regex::new(r"(?m)^\s*Box::new\(day(\d+)::Puzzle[^\n]*\n").unwrap();
+}
+
+/// source of a new day module, with a stub `Puzzle` and tests against the day's example input.
+pub fn module_source(day: usize) -> String {
+    format!(
+        r#"use crate::{{
+    io::parse_lines,
+    result::{{Error, ParseError, Result}},
+    PuzzleRunner,
+}};
+
+pub struct Puzzle;
+
+impl PuzzleRunner for Puzzle {{
+    const DAY: usize = {day};
+    type First = usize;
+    type Second = usize;
+    type Input = Vec<String>;
+
+    fn parse_str(&self, input: &str) -> Result<Self::Input> {{
+        parse_lines(Self::DAY, input, |line| Ok::<_, ParseError>(line.to_string()))
+    }}
+
+    fn part_one(&self, _entries: &Self::Input) -> Result<Self::First> {{
+        Err(Error::NoSolution("part one is not solved yet".into()))
+    }}
+
+    fn part_two(
+        &self,
+        _entries: &Self::Input,
+        _first: Option<&Self::First>,
+    ) -> Result<Self::Second> {{
+        Err(Error::NoSolution("part two is not solved yet".into()))
+    }}
+}}
+
+#[cfg(test)]
+mod test {{
+    use super::Puzzle;
+    use crate::PuzzleRunner;
+
+    #[test]
+    #[ignore = "day {day} is not solved yet"]
+    fn test_part_one() {{
+        let puzzle = Puzzle;
+        let entries = puzzle.test_input().unwrap();
+        assert_eq!(0, puzzle.part_one(&entries).unwrap());
+    }}
+
+    #[test]
+    #[ignore = "day {day} is not solved yet"]
+    fn test_part_two() {{
+        let puzzle = Puzzle;
+        let entries = puzzle.test_input().unwrap();
+        assert_eq!(0, puzzle.part_two(&entries, None).unwrap());
+    }}
+}}
+"#,
+        day = day
+    )
+}
+
+/// adds `pub mod dayN;` to the source of `lib.rs`, after the modules of earlier days.
+pub fn register_module(lib: &str, day: usize) -> Result<String> {
+    insert_after(lib, &MOD_RE, day, &format!("pub mod day{};\n", day))
+        .ok_or_else(|| "no `pub mod dayN;` declarations found in lib.rs".into())
+}
+
+/// adds day `day` to the imports and the puzzle list in the source of `registry.rs`.
+pub fn register_puzzle(registry: &str, day: usize) -> Result<String> {
+    let registry = insert_after(registry, &IMPORT_RE, day, &format!(" day{},", day))
+        .ok_or("no `dayN` imports found in registry.rs")?;
+    insert_after(
+        &registry,
+        &REGISTER_RE,
+        day,
+        &format!("        Box::new(day{}::Puzzle),\n", day),
+    )
+    .ok_or_else(|| "no `Box::new(dayN::Puzzle)` entries found in registry.rs".into())
+}
+
+/// inserts `text` right after the last match of `re` whose day is lower than `day`.
+fn insert_after(source: &str, re: &Regex, day: usize, text: &str) -> Option<String> {
+    let end = re
+        .captures_iter(source)
+        .filter(|c| c[1].parse::<usize>().is_ok_and(|d| d < day))
+        .map(|c| c.get(0).unwrap().end())
+        .last()?;
+    Some(format!("{}{}{}", &source[..end], text, &source[end..]))
+}
+
+/// generates the module, input files and registration for day `day` in the crate at `root`,
+/// returning the paths that were created or changed.
+pub fn new_day(root: &Path, day: usize) -> Result<Vec<String>> {
+    if day == 0 || day > 25 {
+        return Err(format!("invalid day `{}`, expected 1 to 25", day).into());
+    }
+    let module = root.join(format!("src/day{}.rs", day));
+    if module.exists() {
+        return Err(format!("{} already exists", module.display()).into());
+    }
+
+    let lib_path = root.join("src/lib.rs");
+    let registry_path = root.join("src/registry.rs");
+    let lib = fs::read_to_string(&lib_path)?;
+    if lib.contains(&format!("pub mod day{};", day)) {
+        return Err(format!("day {} is already declared in lib.rs", day).into());
+    }
+    // build every change before writing anything, so a failure leaves the tree untouched
+    let lib = register_module(&lib, day)?;
+    let registry = register_puzzle(&fs::read_to_string(&registry_path)?, day)?;
+
+    let mut changed = vec![];
+    fs::write(&module, module_source(day))?;
+    changed.push(module);
+    for data in &[
+        format!("data/day{}.txt", day),
+        format!("data/day{}_test.txt", day),
+    ] {
+        let data = root.join(data);
+        if !data.exists() {
+            fs::write(&data, "")?;
+            changed.push(data);
+        }
+    }
+    fs::write(&lib_path, lib)?;
+    fs::write(&registry_path, registry)?;
+    changed.push(lib_path);
+    changed.push(registry_path);
+
+    // the insertions only keep the sources valid, let rustfmt lay them out when it is around
+    let _ = Command::new("rustfmt")
+        .args(["--edition", "2018"])
+        .args(
+            changed
+                .iter()
+                .filter(|p| p.extension() == Some("rs".as_ref())),
+        )
+        .status();
+
+    Ok(changed.iter().map(|p| p.display().to_string()).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{module_source, register_module, register_puzzle};
+
+    #[test]
+    fn test_module_source() {
+        let source = module_source(10);
+        assert!(source.contains("const DAY: usize = 10;"));
+        assert!(source.contains("fn test_part_one()"));
+        assert!(source.contains("fn test_part_two()"));
+    }
+
+    #[test]
+    fn test_register_module() {
+        let lib = "pub mod bench;\npub mod day1;\npub mod day2;\npub mod io;\n";
+        assert_eq!(
+            "pub mod bench;\npub mod day1;\npub mod day2;\npub mod day3;\npub mod io;\n",
+            register_module(lib, 3).unwrap()
+        );
+        assert!(register_module("pub mod io;\n", 3).is_err());
+    }
+
+    #[test]
+    fn test_register_puzzle() {
+        let registry = "use crate::{day1, day2, result::Result};
+
+pub fn puzzles() -> Vec<Box<dyn DynPuzzle>> {
+    vec![
+        Box::new(day1::Puzzle::default()),
+        Box::new(day2::Puzzle),
+    ]
+}
+";
+        assert_eq!(
+            "use crate::{day1, day2, day3, result::Result};
+
+pub fn puzzles() -> Vec<Box<dyn DynPuzzle>> {
+    vec![
+        Box::new(day1::Puzzle::default()),
+        Box::new(day2::Puzzle),
+        Box::new(day3::Puzzle),
+    ]
+}
+",
+            register_puzzle(registry, 3).unwrap()
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::{any::Any, fmt};
+
+use crate::{
+    registry::{DynPuzzle, SolveContext},
+    result::{Error, Result},
+};
+
+/// a named implementation of a day's parts, as declared by the day. The first strategy a day
+/// declares is the one used unless another is chosen.
+#[derive(Debug, PartialEq)]
+pub struct Strategy {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+impl Strategy {
+    pub fn new(name: &'static str, description: &'static str) -> Self {
+        Self { name, description }
+    }
+}
+
+pub fn unknown_strategy(day: usize, name: &str) -> Error {
+    format!("day {} has no strategy named `{}`", day, name).into()
+}
+
+/// answers every strategy of a day gave for one part of the same input.
+#[derive(Debug, PartialEq)]
+pub struct Crosscheck {
+    pub day: usize,
+    pub part: usize,
+    /// strategy name along with its answer, or the error it failed with
+    pub answers: Vec<(&'static str, String)>,
+}
+
+impl Crosscheck {
+    pub fn agrees(&self) -> bool {
+        self.answers.windows(2).all(|pair| pair[0].1 == pair[1].1)
+    }
+}
+
+/// the answer along with the strategies that agree on it, or every answer when they do not.
+impl fmt::Display for Crosscheck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day {} part {}: ", self.day, self.part)?;
+        if self.agrees() {
+            let names: Vec<&str> = self.answers.iter().map(|(name, _)| *name).collect();
+            let answer = self
+                .answers
+                .first()
+                .map_or("", |(_, answer)| answer.as_str());
+            return write!(f, "{} ({})", answer, names.join(", "));
+        }
+
+        let answers: Vec<String> = self
+            .answers
+            .iter()
+            .map(|(name, answer)| format!("{}={}", name, answer))
+            .collect();
+        write!(f, "MISMATCH {}", answers.join(", "))
+    }
+}
+
+/// solves both parts of `input` with every strategy of `puzzle`, which is left set to its
+/// default strategy afterwards.
+pub fn crosscheck(puzzle: &mut dyn DynPuzzle, input: &dyn Any) -> Result<Vec<Crosscheck>> {
+    let strategies = puzzle.strategies();
+    if strategies.len() < 2 {
+        return Err(format!("day {} has a single strategy", puzzle.day()).into());
+    }
+
+    let mut checks: Vec<Crosscheck> = (1..=2)
+        .map(|part| Crosscheck {
+            day: puzzle.day(),
+            part,
+            answers: vec![],
+        })
+        .collect();
+    for strategy in &strategies {
+        puzzle.set_strategy(strategy.name)?;
+        let mut context = SolveContext::default();
+        for check in checks.iter_mut() {
+            let answer = puzzle
+                .solve(check.part, input, &mut context)
+                .unwrap_or_else(|err| format!("error: {}", err));
+            check.answers.push((strategy.name, answer));
+        }
+    }
+    puzzle.set_strategy(strategies[0].name)?;
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{crosscheck, Crosscheck};
+    use crate::registry::puzzle;
+
+    #[test]
+    fn test_crosscheck() {
+        let mut day7 = puzzle(7).unwrap();
+        let input = day7.parse(Some("./data/day7_test.txt")).unwrap();
+        let checks = crosscheck(day7.as_mut(), input.as_ref()).unwrap();
+        assert_eq!(2, checks.len());
+        assert!(checks.iter().all(Crosscheck::agrees));
+        assert_eq!(("memoized", "32".to_string()), checks[1].answers[1]);
+        assert_eq!(
+            "day 7 part 2: 32 (recursive, memoized)",
+            checks[1].to_string()
+        );
+
+        let mut day2 = puzzle(2).unwrap();
+        let input = day2.parse(Some("./data/day2_test.txt")).unwrap();
+        assert!(crosscheck(day2.as_mut(), input.as_ref()).is_err());
+    }
+
+    #[test]
+    fn test_disagreement() {
+        let check = Crosscheck {
+            day: 1,
+            part: 1,
+            answers: vec![("brute-force", "1".into()), ("hash-set", "2".into())],
+        };
+        assert!(!check.agrees());
+        assert_eq!(
+            "day 1 part 1: MISMATCH brute-force=1, hash-set=2",
+            check.to_string()
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{
+    answers::Answers,
+    bench::time,
+    examples::Example,
+    io::render_error,
+    registry::{DynPuzzle, SolveContext},
+    report::Case,
+};
+
+/// checks the answers for `puzzle` against `answers`, returning a case per part. When the input
+/// does not parse, both parts are errors and the rendered parse error is returned with them.
+pub fn verify(puzzle: &dyn DynPuzzle, answers: &Answers) -> (Vec<Case>, Option<String>) {
+    let suite = format!("day {}", puzzle.day());
+    let input = match puzzle.parse(None) {
+        Ok(input) => input,
+        Err(err) => {
+            let cases = (1..=2)
+                .map(|part| {
+                    let expected = answers.get(puzzle.day(), part);
+                    Case::error(suite.clone(), part_case(part), expected, err.to_string())
+                })
+                .collect();
+            return (cases, Some(render_error(&err, &puzzle.input_path())));
+        }
+    };
+
+    let mut context = SolveContext::default();
+    let cases = (1..=2)
+        .map(|part| {
+            let expected = answers.get(puzzle.day(), part);
+            match time(|| puzzle.solve(part, input.as_ref(), &mut context)) {
+                Ok((answer, elapsed)) => {
+                    Case::checked(suite.clone(), part_case(part), answer, expected, elapsed)
+                }
+                Err(err) => Case::error(suite.clone(), part_case(part), expected, err.to_string()),
+            }
+        })
+        .collect();
+    (cases, None)
+}
+
+/// checks every part of `example` that has an expected answer, returning a case per part.
+pub fn check_example(example: &Example) -> Vec<Case> {
+    let suite = format!("day {}", example.day);
+    (1..=2)
+        .filter_map(|part| {
+            let expected = example.expected[part - 1].as_deref()?;
+            let name = format!("{} {}", example.name, part_case(part));
+            Some(match time(|| example.solve(part)) {
+                Ok((answer, elapsed)) => {
+                    Case::checked(suite.clone(), name, answer, Some(expected), elapsed)
+                }
+                Err(err) => Case::error(suite.clone(), name, Some(expected), err.to_string()),
+            })
+        })
+        .collect()
+}
+
+fn part_case(part: usize) -> String {
+    format!("part {}", part)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_example, verify};
+    use crate::{answers::Answers, examples::Example, registry::puzzle, report::Status};
+
+    #[test]
+    fn test_verify() {
+        let answers = Answers::parse(vec!["8 1 1867".to_string(), "8 2 1".to_string()]).unwrap();
+        let (cases, diagnostic) = verify(puzzle(8).unwrap().as_ref(), &answers);
+        assert_eq!(None, diagnostic);
+        assert_eq!(Status::Passed, cases[0].status);
+        assert_eq!(
+            Status::Failed("expected 1, got 1303".into()),
+            cases[1].status
+        );
+    }
+
+    #[test]
+    fn test_check_example() {
+        let text = "part2: 1\n---\nnop +0\nacc +1\njmp -2\n";
+        let cases = check_example(&Example::parse(8, "loop", text).unwrap());
+        assert_eq!(1, cases.len());
+        assert_eq!("loop part 2", cases[0].name);
+        assert_eq!(Status::Passed, cases[0].status);
+    }
+}
@@ -0,0 +1,12 @@
+use std::path::Path;
+
+use aoc2020::examples::Example;
+
+fn check(path: &str, part: usize) {
+    let example = Example::read(Path::new(path)).unwrap();
+    if let Err(err) = example.check(part) {
+        panic!("{} part {}: {}", path, part, err);
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));